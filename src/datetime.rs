@@ -0,0 +1,249 @@
+use number::Num;
+
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::{Serialize, Serializer};
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::time::Duration;
+
+/// A calendar date.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Date {
+    pub year : i32,
+    pub month : u8,
+    pub day : u8,
+}
+
+/// A time of day, up to the nanosecond.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Time {
+    pub hour : u8,
+    pub minute : u8,
+    pub second : u8,
+    pub nanosecond : u32,
+}
+
+/// An ISO-8601 date and time, as written in SHAUN data: a date
+/// (`2026-10-18`), a local time (`12:00:00`), a local date-time
+/// (`2026-10-18T12:00:00`), or a date-time with its offset from UTC
+/// (`2026-10-18T12:00:00Z`, `2026-10-18T14:00:00+02:00`).
+///
+/// # Examples
+/// ```
+/// use shaun::DateTime;
+///
+/// let dt = DateTime::parse("2026-10-18T14:00:00+02:00").unwrap();
+/// assert_eq!(dt.date().unwrap().month, 10);
+/// assert_eq!(dt.offset(), Some(120));
+/// assert_eq!(dt.unix_timestamp(), DateTime::parse("2026-10-18T12:00:00Z").unwrap().unix_timestamp());
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct DateTime {
+    date : Option<Date>,
+    time : Option<Time>,
+    // minutes east of UTC
+    offset : Option<i32>,
+}
+
+fn number<T : std::str::FromStr>(s:&str, len:usize) -> Option<T> {
+    if s.len() != len || !s.bytes().all(|b| b.is_ascii_digit()) { return None }
+    s.parse().ok()
+}
+
+fn days_in_month(year:i32, month:u8) -> u8 {
+    let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+    match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn parse_date(s:&str) -> Option<Date> {
+    let mut parts = s.splitn(3, '-');
+    let year = number(parts.next()?, 4)?;
+    let month = number(parts.next()?, 2)?;
+    let day = number(parts.next()?, 2)?;
+    if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) { return None }
+    Some(Date { year, month, day })
+}
+
+fn parse_time(s:&str) -> Option<Time> {
+    let (s, fraction) = match s.find('.') {
+        Some(i) => (&s[..i], Some(&s[i+1..])),
+        None => (s, None),
+    };
+    let mut parts = s.splitn(3, ':');
+    let hour = number(parts.next()?, 2)?;
+    let minute = number(parts.next()?, 2)?;
+    let second = match parts.next() { Some(p) => number(p, 2)?, None => 0 };
+    let nanosecond = match fraction {
+        Some(f) if !f.is_empty() && f.len() <= 9 && s.len() == 8 => number::<u32>(f, f.len())? * 10u32.pow(9 - f.len() as u32),
+        Some(_) => return None,
+        None => 0,
+    };
+    if hour > 23 || minute > 59 || second > 59 { return None }
+    Some(Time { hour, minute, second, nanosecond })
+}
+
+fn parse_offset(s:&str) -> Option<i32> {
+    if s == "Z" || s == "z" { return Some(0) }
+    let sign = match s.chars().next()? { '+' => 1, '-' => -1, _ => return None };
+    let (hours, minutes) = s[1..].split_once(':')?;
+    let hours : i32 = number(hours, 2)?;
+    let minutes : i32 = number(minutes, 2)?;
+    if hours > 23 || minutes > 59 { return None }
+    Some(sign * (hours * 60 + minutes))
+}
+
+impl DateTime {
+    /// Reads an ISO-8601 date, time or date-time, None if the text is not
+    /// one of them or names a day or time which does not exist.
+    pub fn parse(text:&str) -> Option<DateTime> {
+        match text.find(['T', 't']) {
+            Some(i) => {
+                let date = parse_date(&text[..i])?;
+                let rest = &text[i+1..];
+                let (time, offset) = match rest.find(['Z', 'z', '+', '-']) {
+                    Some(j) => (&rest[..j], Some(parse_offset(&rest[j..])?)),
+                    None => (rest, None),
+                };
+                Some(DateTime { date: Some(date), time: Some(parse_time(time)?), offset })
+            },
+            None if text.contains(':') => Some(DateTime { date: None, time: Some(parse_time(text)?), offset: None }),
+            None => Some(DateTime { date: Some(parse_date(text)?), time: None, offset: None }),
+        }
+    }
+
+    pub fn date(&self) -> Option<Date> {
+        self.date
+    }
+
+    pub fn time(&self) -> Option<Time> {
+        self.time
+    }
+
+    /// Offset from UTC in minutes, None for local dates and times.
+    pub fn offset(&self) -> Option<i32> {
+        self.offset
+    }
+
+    /// Seconds since 1970-01-01T00:00:00Z, for date-times with an offset.
+    pub fn unix_timestamp(&self) -> Option<i64> {
+        let (date, time, offset) = (self.date?, self.time?, self.offset?);
+
+        // days since the epoch, counting in eras of 400 years starting in March
+        let year = date.year as i64 - if date.month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = date.month as i64;
+        let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + date.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        let days = era * 146_097 + day_of_era - 719_468;
+
+        let seconds = time.hour as i64 * 3600 + time.minute as i64 * 60 + time.second as i64;
+        Some(days * 86_400 + seconds - offset as i64 * 60)
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        if let Some(d) = self.date {
+            write!(f, "{:04}-{:02}-{:02}", d.year, d.month, d.day)?;
+            if self.time.is_some() { write!(f, "T")? }
+        }
+        if let Some(t) = self.time {
+            write!(f, "{:02}:{:02}:{:02}", t.hour, t.minute, t.second)?;
+            if t.nanosecond != 0 {
+                write!(f, ".{}", format!("{:09}", t.nanosecond).trim_end_matches('0'))?;
+            }
+        }
+        match self.offset {
+            Some(0) => write!(f, "Z"),
+            Some(o) => write!(f, "{}{:02}:{:02}", if o < 0 { '-' } else { '+' }, o.abs() / 60, o.abs() % 60),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Serialize for DateTime {
+    fn serialize<S : Serializer>(&self, serializer:S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+struct DateTimeVisitor;
+
+impl<'de> Visitor<'de> for DateTimeVisitor {
+    type Value = DateTime;
+
+    fn expecting(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        write!(f, "an ISO-8601 date or time")
+    }
+
+    fn visit_str<E : de::Error>(self, s:&str) -> Result<DateTime, E> {
+        DateTime::parse(s).ok_or_else(|| E::invalid_value(de::Unexpected::Str(s), &self))
+    }
+}
+
+impl<'de> Deserialize<'de> for DateTime {
+    fn deserialize<D : Deserializer<'de>>(deserializer:D) -> Result<DateTime, D::Error> {
+        deserializer.deserialize_str(DateTimeVisitor)
+    }
+}
+
+/*
+ * nanoseconds() is the length of a unit of duration, in nanoseconds,
+ * None if the unit is not one of duration
+ */
+pub fn nanoseconds(unit:&str) -> Option<u64> {
+    match unit {
+        "ns" => Some(1),
+        "us" | "µs" => Some(1_000),
+        "ms" => Some(1_000_000),
+        "s" => Some(1_000_000_000),
+        "min" => Some(60_000_000_000),
+        "h" => Some(3_600_000_000_000),
+        "d" => Some(86_400_000_000_000),
+        "w" => Some(604_800_000_000_000),
+        _ => None,
+    }
+}
+
+/*
+ * add_durations() sums two durations, as written in a compound literal
+ * such as `1h 30min`, in the smaller of their units
+ */
+pub fn add_durations(a:&Num, unit_a:&str, b:&Num, unit_b:&str) -> Option<(Num, String)> {
+    let (fa, fb) = (nanoseconds(unit_a)?, nanoseconds(unit_b)?);
+    let (unit, f) = if fb < fa { (unit_b.to_string(), fb) } else { (unit_a.to_string(), fa) };
+    let (ra, rb) = ((fa / f) as i128, (fb / f) as i128);
+
+    let exact = match (a.as_i128(), b.as_i128()) {
+        (Some(a), Some(b)) => a.checked_mul(ra).zip(b.checked_mul(rb)).and_then(|(a, b)| a.checked_add(b)),
+        _ => None,
+    };
+    let sum = match exact {
+        Some(i) if i >= 0 => u64::try_from(i).ok().map(Num::UInt),
+        Some(i) => i64::try_from(i).ok().map(Num::Int),
+        None => None,
+    };
+    let sum = sum.unwrap_or_else(|| Num::Float(a.as_f64() * ra as f64 + b.as_f64() * rb as f64));
+    Some((sum, unit))
+}
+
+/*
+ * duration() is the length of a number of a unit of duration, None
+ * for other units and negative lengths
+ */
+pub fn duration(n:&Num, unit:&str) -> Option<Duration> {
+    let f = nanoseconds(unit)?;
+    if let Some(nanos) = n.as_i128().and_then(|i| i.checked_mul(f as i128)) {
+        let nanos = u128::try_from(nanos).ok()?;
+        let seconds = u64::try_from(nanos / 1_000_000_000).ok()?;
+        return Some(Duration::new(seconds, (nanos % 1_000_000_000) as u32))
+    }
+    Duration::try_from_secs_f64(n.as_f64() * f as f64 / 1e9).ok()
+}
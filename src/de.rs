@@ -0,0 +1,116 @@
+use shaun_type::Shaun;
+use number::Num;
+use error::ParseError;
+use parser::parse_reader;
+
+use serde::de::{self, Deserialize, DeserializeOwned, Deserializer, IntoDeserializer};
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+
+use std::io::Read;
+
+/*
+ * Shaun values are their own deserializers: parsed data is first built
+ * into a Shaun tree, then handed to the serde visitor of the target type.
+ * Units of numbers are dropped on the way, except for numbers of units
+ * of duration read as a `std::time::Duration`. Dates and times are
+ * given as ISO-8601 strings.
+ */
+impl<'de> Deserializer<'de> for Shaun {
+    type Error = ParseError;
+
+    fn deserialize_any<V>(self, visitor:V) -> Result<V::Value, ParseError> where V : de::Visitor<'de> {
+        match self {
+            Shaun::Null => visitor.visit_unit(),
+            Shaun::Bool(b) => visitor.visit_bool(b),
+            Shaun::Number(Num::Int(i), _) => visitor.visit_i64(i),
+            Shaun::Number(Num::UInt(u), _) => visitor.visit_u64(u),
+            Shaun::Number(n, _) => {
+                let n = n.as_f64();
+                // integral numbers are given as integers, so they fit integer fields
+                if n.fract() != 0.0 { visitor.visit_f64(n) }
+                else if (0.0..u64::MAX as f64).contains(&n) { visitor.visit_u64(n as u64) }
+                else if (i64::MIN as f64..0.0).contains(&n) { visitor.visit_i64(n as i64) }
+                else { visitor.visit_f64(n) }
+            },
+            Shaun::DateTime(d) => visitor.visit_string(d.to_string()),
+            Shaun::String(s) => visitor.visit_string(s),
+            Shaun::List(l) => {
+                let mut seq = SeqDeserializer::new(l.into_iter());
+                let ret = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(ret)
+            },
+            Shaun::Object(o) => {
+                let mut map = MapDeserializer::new(o.into_iter());
+                let ret = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(ret)
+            },
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor:V) -> Result<V::Value, ParseError> where V : de::Visitor<'de> {
+        match self {
+            Shaun::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_struct<V>(self, name:&'static str, fields:&'static [&'static str], visitor:V)
+        -> Result<V::Value, ParseError> where V : de::Visitor<'de> {
+        match self.as_duration() {
+            Some(d) if name == "Duration" && fields == ["secs", "nanos"] => {
+                let parts = vec![Shaun::from(d.as_secs()), Shaun::from(d.subsec_nanos())];
+                visitor.visit_seq(SeqDeserializer::new(parts.into_iter()))
+            },
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_enum<V>(self, name:&'static str, variants:&'static [&'static str], visitor:V)
+        -> Result<V::Value, ParseError> where V : de::Visitor<'de> {
+        match self {
+            Shaun::String(s) => s.into_deserializer().deserialize_enum(name, variants, visitor),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, ParseError> for Shaun {
+    type Deserializer = Shaun;
+
+    fn into_deserializer(self) -> Shaun {
+        self
+    }
+}
+
+/// Parses SHAUN data from a reader into any deserializable type.
+///
+/// # Examples
+/// ```
+/// extern crate shaun;
+/// #[macro_use]
+/// extern crate serde_derive;
+///
+/// #[derive(Deserialize)]
+/// struct Server { host: String, port: u16 }
+///
+/// # fn main() {
+/// let server : Server = shaun::from_reader("host: \"localhost\" port: 8080".as_bytes()).unwrap();
+/// assert_eq!(server.port, 8080);
+/// # }
+/// ```
+pub fn from_reader<R : Read, T : DeserializeOwned>(reader:R) -> Result<T, ParseError> {
+    T::deserialize(parse_reader(reader)?)
+}
+
+/// Converts a Shaun value into any deserializable type.
+pub fn from_shaun<'de, T : Deserialize<'de>>(value:Shaun) -> Result<T, ParseError> {
+    T::deserialize(value)
+}
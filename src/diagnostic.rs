@@ -0,0 +1,235 @@
+use error::{ErrorKind, ParseError, Position};
+use loader::Span;
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+const RED : &str = "\x1b[1;31m";
+const BLUE : &str = "\x1b[1;34m";
+const BOLD : &str = "\x1b[1m";
+const RESET : &str = "\x1b[0m";
+
+const KEYWORDS : [&str; 3] = ["true", "false", "null"];
+
+/// A message about a place of a SHAUN source, meant for the people
+/// editing it, and rendered as rustc renders its errors: the file and
+/// position, the line of the source, the place underlined, and a hint on
+/// how to fix it, if any.
+///
+/// # Examples
+/// ```
+/// use shaun::{Diagnostic, Reader};
+///
+/// let source = "name: \"app\"\nenabled: true ture\n";
+/// let err = Reader::new(source.as_bytes()).find_map(|e| e.err()).unwrap();
+/// let rendered = Diagnostic::from(&err).in_file("app.sn").render(source);
+/// assert_eq!(rendered, "\
+/// error: unexpected identifier `ture`
+///  --> app.sn:2:15
+///   |
+/// 2 | enabled: true ture
+///   |               ^^^^
+///   |
+///   = help: did you mean `true`?
+/// ");
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    message : String,
+    file : Option<PathBuf>,
+    position : Option<Position>,
+    length : usize,
+    help : Option<String>,
+    colored : bool,
+}
+
+impl Diagnostic {
+    pub fn new<S : Into<String>>(message:S) -> Diagnostic {
+        Diagnostic { message: message.into(), file: None, position: None, length: 1, help: None, colored: false }
+    }
+
+    /// Places the diagnostic at a position, underlining `length` chars.
+    pub fn at(mut self, position:Position, length:usize) -> Diagnostic {
+        self.position = Some(position);
+        self.length = length.max(1);
+        self
+    }
+
+    /// Places the diagnostic where a value was read by a `Loader`.
+    pub fn at_span(self, span:&Span) -> Diagnostic {
+        self.at(span.position, 1).in_file(&span.file)
+    }
+
+    pub fn in_file<P : Into<PathBuf>>(mut self, file:P) -> Diagnostic {
+        self.file = Some(file.into());
+        self
+    }
+
+    pub fn with_help<S : Into<String>>(mut self, help:S) -> Diagnostic {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// Renders with ANSI colours, for terminals.
+    pub fn colored(mut self, colored:bool) -> Diagnostic {
+        self.colored = colored;
+        self
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn position(&self) -> Option<Position> {
+        self.position
+    }
+
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    pub fn help(&self) -> Option<&str> {
+        self.help.as_deref()
+    }
+
+    fn paint(&self, color:&str, text:&str) -> String {
+        if self.colored { format!("{}{}{}", color, text, RESET) } else { text.to_string() }
+    }
+
+    /// Renders the diagnostic, quoting the line of `source` it is about.
+    pub fn render(&self, source:&str) -> String {
+        let mut ret = format!("{}{}\n", self.paint(RED, "error"), self.paint(BOLD, &format!(": {}", self.message)));
+
+        // line 0 is no line of the source, and is rendered without it
+        let line = self.position.and_then(|p| {
+            let index = (p.line as usize).checked_sub(1)?;
+            source.lines().nth(index).map(|l| (p, l))
+        });
+        let width = self.position.map_or(0, |p| p.line.to_string().len());
+        let pad = " ".repeat(width);
+        let gutter = self.paint(BLUE, &format!("{} |", pad));
+
+        if let Some(p) = self.position {
+            let file = self.file.as_ref().map_or("<input>".to_string(), |f| f.display().to_string());
+            ret.push_str(&format!("{}{} {}:{}:{}\n", pad, self.paint(BLUE, "-->"), file, p.line, p.column));
+        }
+        if let Some((p, l)) = line {
+            // tabs are kept, so that the underline stays aligned
+            let before : String = l.chars().take((p.column as usize).saturating_sub(1))
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            ret.push_str(&format!("{}\n", gutter));
+            ret.push_str(&format!("{} {}\n", self.paint(BLUE, &format!("{} |", p.line)), l));
+            ret.push_str(&format!("{} {}{}\n", gutter, before, self.paint(RED, &"^".repeat(self.length))));
+        }
+        if let Some(ref help) = self.help {
+            if line.is_some() { ret.push_str(&format!("{}\n", gutter)) }
+            ret.push_str(&format!("{} {} {}\n", pad, self.paint(BLUE, "="), self.paint(BOLD, &format!("help: {}", help))));
+        }
+        ret
+    }
+}
+
+/*
+ * Only the header is displayed without the source
+ */
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        write!(f, "error: {}", self.message)?;
+        match (self.file.as_ref(), self.position) {
+            (Some(file), Some(p)) => write!(f, " at {}:{}:{}", file.display(), p.line, p.column),
+            (None, Some(p)) => write!(f, " at {}", p),
+            (Some(file), None) => write!(f, " in {}", file.display()),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+impl<'a> From<&'a ParseError> for Diagnostic {
+    fn from(e:&'a ParseError) -> Diagnostic {
+        let mut ret = Diagnostic::new(e.kind().to_string());
+        if let Some(p) = e.position() { ret = ret.at(p, length(e.kind())) }
+        if let Some(file) = e.file() { ret = ret.in_file(file) }
+        ret.help = help(e.kind());
+        ret
+    }
+}
+
+/*
+ * quoted() is the text between backticks of a message, such as the
+ * identifier of "identifier `ture`"
+ */
+fn quoted(s:&str) -> Option<&str> {
+    let start = s.find('`')?;
+    let end = s.rfind('`')?;
+    if end > start { Some(&s[start + 1..end]) } else { None }
+}
+
+/*
+ * length() is the number of chars to underline for an error
+ */
+fn length(kind:&ErrorKind) -> usize {
+    match *kind {
+        ErrorKind::UnexpectedToken(ref t) => quoted(t).map_or(1, |q| q.chars().count()),
+        ErrorKind::InvalidNumber(ref s) | ErrorKind::InvalidDateTime(ref s) | ErrorKind::InvalidEscape(ref s)
+            | ErrorKind::DuplicateKey(ref s) => s.chars().count(),
+        ErrorKind::UnknownVariable(ref v) => v.chars().count() + 3,
+        ErrorKind::DanglingReference(ref a) | ErrorKind::CyclicReference(ref a) => a.chars().count() + 1,
+        _ => 1,
+    }
+}
+
+/*
+ * distance() is the number of chars to insert, remove, replace or swap
+ * with the next one to turn a into b
+ */
+fn distance(a:&str, b:&str) -> usize {
+    let (a, b) : (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() { row[0] = i }
+    for (j, cell) in d[0].iter_mut().enumerate() { *cell = j }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/*
+ * suggestion() is the keyword a word is likely a typo of
+ */
+pub fn suggestion(word:&str) -> Option<&'static str> {
+    let word = word.to_lowercase();
+    let max = if word.len() <= 4 { 1 } else { 2 };
+    KEYWORDS.iter().cloned()
+        .filter(|k| *k != word)
+        .map(|k| (distance(&word, k), k))
+        .filter(|&(d, _)| d <= max)
+        .min()
+        .map(|(_, k)| k)
+}
+
+fn help(kind:&ErrorKind) -> Option<String> {
+    match *kind {
+        ErrorKind::UnexpectedToken(ref t) => {
+            let word = quoted(t)?;
+            match suggestion(word) {
+                Some(k) => Some(format!("did you mean `{}`?", k)),
+                None if t.starts_with("identifier") => Some(format!("a key is followed by `:`, as in `{}: value`", word)),
+                None => None,
+            }
+        },
+        ErrorKind::UnexpectedEof => Some("an object, a list or a string may be missing its closing `}`, `]` or quote".to_string()),
+        ErrorKind::InvalidNumber(_) => Some("numbers are written as `42`, `-1.5`, `6.02e23` or `0xFF`".to_string()),
+        ErrorKind::InvalidDateTime(_) => Some("dates and times are written as `2026-10-18`, `12:30:00` or `2026-10-18T12:30:00Z`".to_string()),
+        ErrorKind::InvalidEscape(_) => Some("write `\\\\` for a backslash, or use a raw string such as `r\"C:\\dir\"`".to_string()),
+        ErrorKind::DuplicateKey(_) => Some("a key may only be given once in an object".to_string()),
+        ErrorKind::DanglingReference(ref a) => Some(format!("name the value to copy with `&{}`", a)),
+        _ => None,
+    }
+}
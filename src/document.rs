@@ -0,0 +1,271 @@
+use shaun_type::Shaun;
+use error::{ErrorKind, ParseError, Position};
+use options::Limit;
+use path::{self, PathDisplay, Segment};
+use expr::{Expr, MAX_NESTING};
+use writer::Writer;
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io;
+use std::io::Write;
+
+/*
+ * References holds the anchors, aliases and expressions found while
+ * building a document, by path of the value they apply to
+ */
+#[derive(Debug, Default, Clone)]
+pub struct References {
+    anchors : HashMap<String, (Vec<Segment>, Position)>,
+    aliases : BTreeMap<Vec<Segment>, (String, Position)>,
+    expressions : BTreeMap<Vec<Segment>, (String, Position)>,
+}
+
+impl References {
+    pub fn anchor(&mut self, name:String, path:Vec<Segment>, at:Position) -> Result<(), ParseError> {
+        if self.anchors.contains_key(&name) {
+            return Err(ParseError::new(ErrorKind::DuplicateAnchor(name), at))
+        }
+        self.anchors.insert(name, (path, at));
+        Ok(())
+    }
+
+    pub fn alias(&mut self, name:String, path:Vec<Segment>, at:Position) {
+        self.aliases.insert(path, (name, at));
+    }
+
+    pub fn expression(&mut self, text:String, path:Vec<Segment>, at:Position) {
+        self.expressions.insert(path, (text, at));
+    }
+
+    /*
+     * canonical() follows the aliases leading to a path, so that the
+     * path refers to the anchored value rather than to its copy
+     */
+    fn canonical(&self, p:&[Segment]) -> Vec<Segment> {
+        let mut ret = p.to_vec();
+        for _ in 0..=self.aliases.len() {
+            let target = self.aliases.iter()
+                .find(|&(a, _)| ret.starts_with(a))
+                .and_then(|(a, (name, _))| self.anchors.get(name).map(|(t, _)| (a.len(), t)));
+            match target {
+                Some((n, t)) => ret = t.iter().chain(ret[n..].iter()).cloned().collect(),
+                None => break,
+            }
+        }
+        ret
+    }
+}
+
+/// A parsed document whose references have not been resolved.
+///
+/// Aliases (`*name`) and expressions (`$(a + b)`) read as `Null` in its
+/// value, until the document is resolved. A document can also be written
+/// back with its anchors, aliases and expressions, as they were read.
+///
+/// # Examples
+/// ```
+/// use shaun::{parse_unresolved, Shaun};
+///
+/// let doc = parse_unresolved("base: &b { port: 80 } web: *b".as_bytes()).unwrap();
+/// assert_eq!(doc.value().get("web").unwrap(), &Shaun::Null);
+///
+/// let sn = doc.resolve().unwrap();
+/// assert_eq!(sn.get("web").unwrap(), sn.get("base").unwrap());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Document {
+    value : Shaun,
+    refs : References,
+    max_values : Option<u64>,
+}
+
+impl Document {
+    pub fn new(value:Shaun, refs:References) -> Document {
+        Document { value, refs, max_values: None }
+    }
+
+    /// Fails to resolve aliases which would make the value hold more
+    /// than `max` values, with `ErrorKind::LimitExceeded`.
+    pub fn max_values(mut self, max:Option<u64>) -> Document {
+        self.max_values = max;
+        self
+    }
+
+    pub fn value(&self) -> &Shaun {
+        &self.value
+    }
+
+    /// Whether the document holds no alias nor expression to resolve.
+    pub fn is_resolved(&self) -> bool {
+        self.refs.aliases.is_empty() && self.refs.expressions.is_empty()
+    }
+
+    /// Replaces every expression by its value, then every alias by a copy
+    /// of its anchored value. Aliases to unknown anchors, or to values
+    /// containing the alias itself, are errors, as are invalid expressions
+    /// and expressions depending on themselves.
+    pub fn resolve(self) -> Result<Shaun, ParseError> {
+        self.resolve_with(&mut Err)
+    }
+
+    /// Resolves what can be, leaving null the aliases and expressions
+    /// which cannot, and returns every error found.
+    pub fn resolve_all(self) -> (Shaun, Vec<ParseError>) {
+        let mut errors : Vec<ParseError> = Vec::new();
+        let value = self.resolve_with(&mut |e| {
+            // expressions depending on one in error fail with the same error
+            let seen = errors.iter().any(|x| x.position() == e.position() && x.to_string() == e.to_string());
+            if !seen { errors.push(e) }
+            Ok(())
+        });
+        (value.unwrap_or(Shaun::Null), errors)
+    }
+
+    fn resolve_with(self, on_error:&mut dyn FnMut(ParseError) -> Result<(), ParseError>) -> Result<Shaun, ParseError> {
+        let Document { mut value, refs, max_values } = self;
+        let mut done = HashSet::new();
+        for p in refs.expressions.keys() {
+            if let Err(e) = evaluate(&mut value, &refs, p, &mut done, &mut Vec::new()) { on_error(e)? }
+        }
+
+        let mut aliases = Aliases { done: HashSet::new(), visiting: Vec::new(), values: count(&value), max_values };
+        for p in refs.aliases.keys() {
+            if let Err(e) = resolve_alias(&mut value, &refs, p, &mut aliases) { on_error(e)? }
+        }
+        Ok(value)
+    }
+
+    /// Writes the document, with its anchors and aliases.
+    pub fn write<W : Write>(&self, w:&mut Writer<W>) -> io::Result<()> {
+        let anchors : HashMap<Vec<Segment>, &str> = self.refs.anchors.iter()
+            .map(|(name, (p, _))| (p.clone(), name.as_ref()))
+            .collect();
+        self.write_at(w, &self.value, &mut Vec::new(), &anchors)
+    }
+
+    fn write_at<W : Write>(&self, w:&mut Writer<W>, sn:&Shaun, path:&mut Vec<Segment>,
+                           anchors:&HashMap<Vec<Segment>, &str>) -> io::Result<()> {
+        if let Some(name) = anchors.get(&path[..]) { w.anchor(name)? }
+        if let Some((name, _)) = self.refs.aliases.get(&path[..]) { return w.alias(name) }
+        if let Some((text, _)) = self.refs.expressions.get(&path[..]) { return w.expression(text) }
+
+        match *sn {
+            Shaun::List(ref l) => {
+                w.begin_list()?;
+                for (i, v) in l.iter().enumerate() {
+                    path.push(Segment::Index(i));
+                    self.write_at(w, v, path, anchors)?;
+                    path.pop();
+                }
+                w.end_list()
+            },
+            Shaun::Object(ref o) => {
+                w.begin_object()?;
+                for (k, v) in o.iter() {
+                    w.key(k)?;
+                    path.push(Segment::Key(k.clone()));
+                    self.write_at(w, v, path, anchors)?;
+                    path.pop();
+                }
+                w.end_object()
+            },
+            _ => w.value(sn),
+        }
+    }
+}
+
+/*
+ * Aliases tracks the resolution of aliases: those resolved, those being
+ * resolved, to detect cycles, and the number of values of the document
+ */
+struct Aliases {
+    done : HashSet<Vec<Segment>>,
+    visiting : Vec<Vec<Segment>>,
+    values : u64,
+    max_values : Option<u64>,
+}
+
+/*
+ * count() is the number of values of a value, itself included
+ */
+fn count(sn:&Shaun) -> u64 {
+    match *sn {
+        Shaun::List(ref l) => 1 + l.iter().map(count).sum::<u64>(),
+        Shaun::Object(ref o) => 1 + o.values().map(count).sum::<u64>(),
+        _ => 1,
+    }
+}
+
+/*
+ * resolve_alias() replaces the alias at path p, once every alias within
+ * its anchored value has been resolved, unless the copy makes the
+ * document hold too many values
+ */
+fn resolve_alias(value:&mut Shaun, refs:&References, p:&[Segment], aliases:&mut Aliases) -> Result<(), ParseError> {
+    if aliases.done.contains(p) { return Ok(()) }
+
+    let (ref name, at) = refs.aliases[p];
+    let target = match refs.anchors.get(name) {
+        Some((target, _)) => target,
+        None => return Err(ParseError::new(ErrorKind::DanglingReference(name.clone()), at)),
+    };
+    if aliases.visiting.iter().any(|v| v[..] == *p) {
+        return Err(ParseError::new(ErrorKind::CyclicReference(name.clone()), at))
+    }
+
+    aliases.visiting.push(p.to_vec());
+    for q in refs.aliases.keys().filter(|q| q.starts_with(target)) {
+        resolve_alias(value, refs, q, aliases)?;
+    }
+    aliases.visiting.pop();
+
+    let copied = path::get(value, target).map_or(1, count);
+    // the copy replaces the null standing for the alias
+    let values = aliases.values + copied - 1;
+    if let Some(max) = aliases.max_values.filter(|&max| values > max) {
+        return Err(ParseError::new(ErrorKind::LimitExceeded(Limit::Values, max), at))
+    }
+    aliases.values = values;
+
+    let copy = path::get(value, target).cloned().unwrap_or(Shaun::Null);
+    if let Some(v) = path::get_mut(value, p) { *v = copy }
+    aliases.done.insert(p.to_vec());
+    Ok(())
+}
+
+/*
+ * evaluate() replaces the expression at path p by its value. The keys
+ * it refers to are looked up relative to the objects containing it,
+ * innermost first, after evaluating the expressions they hold.
+ */
+fn evaluate(value:&mut Shaun, refs:&References, p:&[Segment], done:&mut HashSet<Vec<Segment>>,
+            visiting:&mut Vec<Vec<Segment>>) -> Result<(), ParseError> {
+    if done.contains(p) { return Ok(()) }
+
+    let (ref text, at) = refs.expressions[p];
+    if visiting.iter().any(|v| v[..] == *p) {
+        return Err(ParseError::new(ErrorKind::CyclicExpression(PathDisplay(p).to_string()), at))
+    }
+    let expr = Expr::parse(text, MAX_NESTING).map_err(|k| ParseError::new(k, at))?;
+
+    visiting.push(p.to_vec());
+    let ret = expr.eval(Some(at), &mut |r: &[Segment]| {
+        for n in (0..p.len()).rev() {
+            let target = refs.canonical(&[&p[..n], r].concat());
+            let pending : Vec<Vec<Segment>> = refs.expressions.keys()
+                .filter(|q| q.starts_with(&target) || target.starts_with(q))
+                .cloned().collect();
+            for q in pending {
+                evaluate(value, refs, &q, done, visiting)?;
+            }
+            if let Some(sn) = path::get(value, &target) { return Ok(Some(sn.clone())) }
+        }
+        Ok(None)
+    });
+    visiting.pop();
+
+    let ret = ret?;
+    if let Some(v) = path::get_mut(value, p) { *v = ret }
+    done.insert(p.to_vec());
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use options::Limit;
+
+/// A location in a SHAUN source, both line and column starting at 1.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Position {
+    pub line : u32,
+    pub column : u32,
+}
+
+impl Position {
+    pub fn start() -> Position {
+        Position { line: 1, column: 1 }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// What went wrong while reading SHAUN data.
+#[derive(Debug)]
+pub enum ErrorKind {
+    Io(io::Error),
+    InvalidUtf8,
+    UnexpectedEof,
+    UnexpectedToken(String),
+    InvalidNumber(String),
+    InvalidDateTime(String),
+    InvalidEscape(String),
+    LimitExceeded(Limit, u64),
+    Message(String),
+    UnresolvedInclude(String),
+    IncludeNotFound(String),
+    IncludeCycle(PathBuf),
+    IncludeDepth(usize),
+    DuplicateAnchor(String),
+    DuplicateKey(String),
+    DanglingReference(String),
+    CyclicReference(String),
+    UnknownVariable(String),
+    InvalidExpression(String),
+    CyclicExpression(String),
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ErrorKind::Io(ref e) => write!(f, "I/O error: {}", e),
+            ErrorKind::InvalidUtf8 => write!(f, "invalid UTF-8 sequence"),
+            ErrorKind::UnexpectedEof => write!(f, "unexpected end of input"),
+            ErrorKind::UnexpectedToken(ref t) => write!(f, "unexpected {}", t),
+            ErrorKind::InvalidNumber(ref n) => write!(f, "invalid number `{}`", n),
+            ErrorKind::InvalidDateTime(ref d) => write!(f, "invalid date or time `{}`", d),
+            ErrorKind::InvalidEscape(ref e) => write!(f, "invalid escape `{}` in string", e),
+            ErrorKind::LimitExceeded(l, n) => write!(f, "{} exceeds the limit of {}", l, n),
+            ErrorKind::Message(ref m) => write!(f, "{}", m),
+            ErrorKind::UnresolvedInclude(ref p) => write!(f, "cannot include `{}` without a Loader", p),
+            ErrorKind::IncludeNotFound(ref p) => write!(f, "included file `{}` not found", p),
+            ErrorKind::IncludeCycle(ref p) => write!(f, "`{}` includes itself", p.display()),
+            ErrorKind::IncludeDepth(d) => write!(f, "includes nested deeper than {} files", d),
+            ErrorKind::DuplicateAnchor(ref a) => write!(f, "anchor `&{}` defined twice", a),
+            ErrorKind::DuplicateKey(ref k) => write!(f, "key `{}` defined twice", k),
+            ErrorKind::DanglingReference(ref a) => write!(f, "no anchor `&{}` for alias `*{}`", a, a),
+            ErrorKind::CyclicReference(ref a) => write!(f, "alias `*{}` refers to a value containing itself", a),
+            ErrorKind::UnknownVariable(ref v) => write!(f, "unknown variable `{}`", v),
+            ErrorKind::InvalidExpression(ref m) => write!(f, "invalid expression: {}", m),
+            ErrorKind::CyclicExpression(ref p) => write!(f, "expression at `{}` depends on itself", p),
+        }
+    }
+}
+
+/// Error returned when SHAUN data cannot be read, with the position
+/// where the problem was found, if any.
+#[derive(Debug)]
+pub struct ParseError {
+    kind : ErrorKind,
+    position : Option<Position>,
+    file : Option<PathBuf>,
+}
+
+impl ParseError {
+    pub fn new(kind:ErrorKind, position:Position) -> ParseError {
+        ParseError { kind, position: Some(position), file: None }
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    pub fn into_kind(self) -> ErrorKind {
+        self.kind
+    }
+
+    pub fn position(&self) -> Option<Position> {
+        self.position
+    }
+
+    /// Line of the error, starting at 1, or 0 if it has no position.
+    pub fn line(&self) -> u32 {
+        self.position.map_or(0, |p| p.line)
+    }
+
+    /// Column of the error, starting at 1, or 0 if it has no position.
+    pub fn column(&self) -> u32 {
+        self.position.map_or(0, |p| p.column)
+    }
+
+    /// File where the error was found, when read through a `Loader`.
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    /// Sets the file where the error was found, unless already known.
+    pub fn in_file<P : Into<PathBuf>>(mut self, file:P) -> ParseError {
+        if self.file.is_none() { self.file = Some(file.into()) }
+        self
+    }
+}
+
+/// An error found outside of a source, such as an unknown variable of
+/// an already parsed value, has no position.
+impl From<ErrorKind> for ParseError {
+    fn from(kind:ErrorKind) -> ParseError {
+        ParseError { kind, position: None, file: None }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.kind)?;
+        if let Some(ref file) = self.file { write!(f, " in {}", file.display())?; }
+        match self.position {
+            Some(ref p) => write!(f, " at {}", p),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self.kind {
+            ErrorKind::Io(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl ::serde::de::Error for ParseError {
+    fn custom<T : fmt::Display>(msg:T) -> ParseError {
+        ParseError { kind: ErrorKind::Message(msg.to_string()), position: None, file: None }
+    }
+}
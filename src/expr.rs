@@ -0,0 +1,550 @@
+use shaun_type::Shaun;
+use error::{ErrorKind, ParseError, Position};
+use path::{self, Segment};
+use number::Num;
+use options::Limit;
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Op {
+    Add, Sub, Mul, Div, Rem,
+    Eq, Ne, Lt, Le, Gt, Ge,
+    And, Or,
+}
+
+/*
+ * Expr is the syntax tree of an expression, as read between `$(` and `)`
+ */
+#[derive(Debug, PartialEq, Clone)]
+pub enum Expr {
+    Value(Shaun),
+    List(Vec<Expr>),
+    Ref(Vec<Segment>),
+    Neg(Box<Expr>),
+    Not(Box<Expr>),
+    // a left-associative chain of operators of the same precedence, such
+    // as `a + b - c`, kept flat so that long chains need no recursion
+    Binary(Box<Expr>, Vec<(Op, Expr)>),
+    Call(String, Vec<Expr>),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Tok {
+    Num(Num),
+    Str(String),
+    Id(String),
+    Punct(&'static str),
+}
+
+/// Maximum nesting of parentheses, lists, calls and signs in an
+/// expression, unless `ParseOptions::max_depth` is lower.
+pub const MAX_NESTING : usize = 64;
+
+fn tokenize(text:&str) -> Result<Vec<Tok>, String> {
+    let mut ret = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() { chars.next(); }
+        else if c.is_ascii_digit() { ret.push(Tok::Num(number(&mut chars)?)) }
+        else if c.is_alphabetic() || c == '_' {
+            let mut id = String::new();
+            while let Some(&c) = chars.peek() {
+                if !(c.is_alphanumeric() || c == '_') { break }
+                id.push(c);
+                chars.next();
+            }
+            ret.push(Tok::Id(id));
+        }
+        else if c == '"' {
+            chars.next();
+            let mut s = String::new();
+            loop {
+                match chars.next() {
+                    None => return Err("unterminated string".to_string()),
+                    Some('"') => break,
+                    Some('\\') => match chars.next() {
+                        Some('n') => s.push('\n'),
+                        Some('t') => s.push('\t'),
+                        Some('r') => s.push('\r'),
+                        Some(c) => s.push(c),
+                        None => return Err("unterminated string".to_string()),
+                    },
+                    Some(c) => s.push(c),
+                }
+            }
+            ret.push(Tok::Str(s));
+        }
+        else {
+            chars.next();
+            ret.push(Tok::Punct(punct(c, &mut chars).ok_or_else(|| format!("unexpected `{}`", c))?));
+        }
+    }
+
+    Ok(ret)
+}
+
+/*
+ * punct() is the operator or punctuation starting with c, reading its
+ * second char if it has one
+ */
+fn punct(c:char, chars:&mut Peekable<Chars>) -> Option<&'static str> {
+    let second = |chars:&mut Peekable<Chars>, n:char| chars.next_if_eq(&n).is_some();
+    Some(match c {
+        '=' if second(chars, '=') => "==",
+        '!' if second(chars, '=') => "!=",
+        '<' if second(chars, '=') => "<=",
+        '>' if second(chars, '=') => ">=",
+        '&' if second(chars, '&') => "&&",
+        '|' if second(chars, '|') => "||",
+        '+' => "+", '-' => "-", '*' => "*", '/' => "/", '%' => "%",
+        '<' => "<", '>' => ">", '!' => "!",
+        '(' => "(", ')' => ")", '[' => "[", ']' => "]", ',' => ",", '.' => ".",
+        _ => return None,
+    })
+}
+
+fn number(chars:&mut Peekable<Chars>) -> Result<Num, String> {
+    let mut buffer = String::new();
+    while let Some(&c) = chars.peek() {
+        let exponent_sign = (c == '-' || c == '+') && buffer.ends_with(['e', 'E']);
+        if !(c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || exponent_sign) { break }
+        buffer.push(c);
+        chars.next();
+    }
+    match buffer.parse() {
+        Ok(u) => Ok(Num::UInt(u)),
+        Err(_) => buffer.parse().map(Num::Float).map_err(|_| format!("invalid number `{}`", buffer)),
+    }
+}
+
+/*
+ * Parser reads tokens by recursive descent, each function handling one
+ * level of precedence, from || (loosest) to postfix paths (tightest).
+ * Only nesting recurses, and it is limited to max_depth levels.
+ */
+struct Parser {
+    toks : Vec<Tok>,
+    pos : usize,
+    depth : usize,
+    max_depth : usize,
+}
+
+fn invalid<S : Into<String>>(m:S) -> ErrorKind {
+    ErrorKind::InvalidExpression(m.into())
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Tok> {
+        self.toks.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Tok> {
+        let ret = self.toks.get(self.pos).cloned();
+        self.pos += 1;
+        ret
+    }
+
+    fn eat(&mut self, p:&str) -> bool {
+        if matches!(self.peek(), Some(&Tok::Punct(q)) if q == p) {
+            self.pos += 1;
+            true
+        }
+        else { false }
+    }
+
+    fn expect(&mut self, p:&str) -> Result<(), ErrorKind> {
+        if self.eat(p) { Ok(()) }
+        else { Err(invalid(format!("expected `{}`", p))) }
+    }
+
+    /*
+     * nest() counts one more level of nesting, failing past max_depth
+     */
+    fn nest(&mut self) -> Result<(), ErrorKind> {
+        self.depth += 1;
+        if self.depth > self.max_depth { Err(ErrorKind::LimitExceeded(Limit::Depth, self.max_depth as u64)) }
+        else { Ok(()) }
+    }
+
+    fn binary(&mut self, ops:&[(&str, Op)], operand:fn(&mut Parser) -> Result<Expr, ErrorKind>) -> Result<Expr, ErrorKind> {
+        let first = operand(self)?;
+        let mut rest = Vec::new();
+        'outer: loop {
+            for &(p, op) in ops {
+                if self.eat(p) {
+                    rest.push((op, operand(self)?));
+                    continue 'outer
+                }
+            }
+            break
+        }
+        Ok(if rest.is_empty() { first } else { Expr::Binary(Box::new(first), rest) })
+    }
+
+    fn or(&mut self) -> Result<Expr, ErrorKind> {
+        self.binary(&[("||", Op::Or)], Parser::and)
+    }
+
+    fn and(&mut self) -> Result<Expr, ErrorKind> {
+        self.binary(&[("&&", Op::And)], Parser::comparison)
+    }
+
+    fn comparison(&mut self) -> Result<Expr, ErrorKind> {
+        self.binary(&[("==", Op::Eq), ("!=", Op::Ne), ("<=", Op::Le), (">=", Op::Ge), ("<", Op::Lt), (">", Op::Gt)],
+                    Parser::additive)
+    }
+
+    fn additive(&mut self) -> Result<Expr, ErrorKind> {
+        self.binary(&[("+", Op::Add), ("-", Op::Sub)], Parser::multiplicative)
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr, ErrorKind> {
+        self.binary(&[("*", Op::Mul), ("/", Op::Div), ("%", Op::Rem)], Parser::unary)
+    }
+
+    fn unary(&mut self) -> Result<Expr, ErrorKind> {
+        let depth = self.depth;
+        let mut signs = Vec::new();
+        loop {
+            if self.eat("-") { signs.push(true) }
+            else if self.eat("!") { signs.push(false) }
+            else { break }
+            self.nest()?;
+        }
+        let mut ret = self.primary()?;
+        for neg in signs.into_iter().rev() {
+            ret = if neg { Expr::Neg(Box::new(ret)) } else { Expr::Not(Box::new(ret)) };
+        }
+        self.depth = depth;
+        Ok(ret)
+    }
+
+    /*
+     * nested() reads the expression between brackets, the opening one
+     * already read
+     */
+    fn nested(&mut self) -> Result<Expr, ErrorKind> {
+        self.nest()?;
+        let ret = self.or()?;
+        self.expect(")")?;
+        self.depth -= 1;
+        Ok(ret)
+    }
+
+    fn arguments(&mut self, end:&str) -> Result<Vec<Expr>, ErrorKind> {
+        self.nest()?;
+        let mut ret = Vec::new();
+        if !self.eat(end) {
+            loop {
+                ret.push(self.or()?);
+                if self.eat(end) { break }
+                self.expect(",")?;
+            }
+        }
+        self.depth -= 1;
+        Ok(ret)
+    }
+
+    fn primary(&mut self) -> Result<Expr, ErrorKind> {
+        match self.next() {
+            // a number may be followed by its unit, as in SHAUN
+            Some(Tok::Num(n)) => match self.peek() {
+                Some(Tok::Id(u)) if self.toks.get(self.pos + 1) != Some(&Tok::Punct("(")) => {
+                    let unit = u.clone();
+                    self.pos += 1;
+                    Ok(Expr::Value(Shaun::Number(n, unit)))
+                },
+                _ => Ok(Expr::Value(Shaun::Number(n, String::new()))),
+            },
+            Some(Tok::Str(s)) => Ok(Expr::Value(Shaun::String(s))),
+            Some(Tok::Id(ref id)) if id == "true" => Ok(Expr::Value(Shaun::Bool(true))),
+            Some(Tok::Id(ref id)) if id == "false" => Ok(Expr::Value(Shaun::Bool(false))),
+            Some(Tok::Id(ref id)) if id == "null" => Ok(Expr::Value(Shaun::Null)),
+            Some(Tok::Id(id)) => {
+                if self.eat("(") { return Ok(Expr::Call(id, self.arguments(")")?)) }
+
+                let mut path = vec![Segment::Key(id)];
+                loop {
+                    if self.eat(".") {
+                        match self.next() {
+                            Some(Tok::Id(k)) => path.push(Segment::Key(k)),
+                            _ => return Err(invalid("expected a key after `.`")),
+                        }
+                    }
+                    else if self.eat("[") {
+                        match self.next() {
+                            Some(Tok::Num(Num::UInt(n))) => path.push(Segment::Index(n as usize)),
+                            _ => return Err(invalid("expected an index after `[`")),
+                        }
+                        self.expect("]")?;
+                    }
+                    else { return Ok(Expr::Ref(path)) }
+                }
+            },
+            Some(Tok::Punct("(")) => self.nested(),
+            Some(Tok::Punct("[")) => Ok(Expr::List(self.arguments("]")?)),
+            Some(Tok::Punct(p)) => Err(invalid(format!("unexpected `{}`", p))),
+            None => Err(invalid("unexpected end of expression")),
+        }
+    }
+}
+
+fn type_name(sn:&Shaun) -> &'static str {
+    match *sn {
+        Shaun::Null => "null",
+        Shaun::Bool(_) => "boolean",
+        Shaun::Number(_, _) => "number",
+        Shaun::DateTime(_) => "datetime",
+        Shaun::String(_) => "string",
+        Shaun::List(_) => "list",
+        Shaun::Object(_) => "object",
+    }
+}
+
+fn text(sn:&Shaun) -> Option<String> {
+    match *sn {
+        Shaun::String(ref s) => Some(s.clone()),
+        Shaun::Number(ref n, ref u) if u.is_empty() => Some(n.to_string()),
+        Shaun::Number(ref n, ref u) => Some(format!("{} {}", n, u)),
+        Shaun::Bool(b) => Some(b.to_string()),
+        Shaun::DateTime(ref d) => Some(d.to_string()),
+        _ => None,
+    }
+}
+
+/*
+ * unit() is the unit of the result of an operation on numbers of units
+ * a and b: a number without unit takes the unit of the other
+ */
+fn unit<'a>(a:&'a str, b:&'a str) -> Result<&'a str, String> {
+    if a.is_empty() || a == b { Ok(b) }
+    else if b.is_empty() { Ok(a) }
+    else { Err(format!("mismatched units `{}` and `{}`", a, b)) }
+}
+
+fn integer(i:i128) -> Num {
+    if i >= 0 && i <= u64::MAX as i128 { Num::UInt(i as u64) }
+    else if i >= i64::MIN as i128 && i < 0 { Num::Int(i as i64) }
+    else { Num::Float(i as f64) }
+}
+
+/*
+ * number_op() keeps integers exact, unless the result is not an integer
+ */
+fn number_op(op:Op, a:Num, b:Num) -> Num {
+    let exact = match (a.as_i128(), b.as_i128()) {
+        (Some(x), Some(y)) => match op {
+            Op::Add => Some(x + y),
+            Op::Sub => Some(x - y),
+            Op::Mul => x.checked_mul(y),
+            Op::Div if y != 0 && x % y == 0 => Some(x / y),
+            Op::Rem if y != 0 => Some(x % y),
+            _ => None,
+        },
+        _ => None,
+    };
+    if let Some(i) = exact { return integer(i) }
+
+    let (x, y) = (a.as_f64(), b.as_f64());
+    Num::Float(match op {
+        Op::Add => x + y,
+        Op::Sub => x - y,
+        Op::Mul => x * y,
+        Op::Div => x / y,
+        _ => x % y,
+    })
+}
+
+fn arithmetic(op:Op, a:Shaun, b:Shaun) -> Result<Shaun, String> {
+    match (op, a, b) {
+        (Op::Add, Shaun::String(a), b) => match text(&b) {
+            Some(b) => Ok(Shaun::String(a + &b)),
+            None => Err(format!("cannot add a {} to a string", type_name(&b))),
+        },
+        (Op::Add, a, Shaun::String(b)) => match text(&a) {
+            Some(a) => Ok(Shaun::String(a + &b)),
+            None => Err(format!("cannot add a string to a {}", type_name(&a))),
+        },
+        (Op::Add, Shaun::List(mut a), Shaun::List(b)) => { a.extend(b); Ok(Shaun::List(a)) },
+        (op, Shaun::Number(a, ua), Shaun::Number(b, ub)) => {
+            let unit = match op {
+                Op::Add | Op::Sub | Op::Rem => unit(&ua, &ub)?.to_string(),
+                Op::Mul if ua.is_empty() || ub.is_empty() => ua + &ub,
+                Op::Div if ub.is_empty() => ua,
+                // dividing quantities of the same unit gives a ratio
+                Op::Div if ua == ub => String::new(),
+                _ => return Err(format!("cannot combine units `{}` and `{}`", ua, ub)),
+            };
+            Ok(Shaun::Number(number_op(op, a, b), unit))
+        },
+        (_, a, b) => Err(format!("invalid operands, {} and {}", type_name(&a), type_name(&b))),
+    }
+}
+
+fn comparison(op:Op, a:&Shaun, b:&Shaun) -> Result<bool, String> {
+    let ordering = match (a, b) {
+        (Shaun::Number(a, ua), Shaun::Number(b, ub)) => { unit(ua, ub)?; a.partial_cmp(b) },
+        (Shaun::String(a), Shaun::String(b)) => Some(a.cmp(b)),
+        _ if op == Op::Eq => return Ok(a == b),
+        _ if op == Op::Ne => return Ok(a != b),
+        _ => return Err(format!("cannot compare a {} and a {}", type_name(a), type_name(b))),
+    };
+
+    Ok(match ordering {
+        None => op == Op::Ne,
+        Some(o) => match op {
+            Op::Eq => o.is_eq(),
+            Op::Ne => o.is_ne(),
+            Op::Lt => o.is_lt(),
+            Op::Le => o.is_le(),
+            Op::Gt => o.is_gt(),
+            _ => o.is_ge(),
+        },
+    })
+}
+
+fn boolean(sn:Shaun) -> Result<bool, String> {
+    match sn {
+        Shaun::Bool(b) => Ok(b),
+        _ => Err(format!("expected a boolean, found a {}", type_name(&sn))),
+    }
+}
+
+/*
+ * extremum() implements min() and max(), taking numbers or a list of
+ * numbers; greater tells which one to keep
+ */
+fn extremum(name:&str, args:Vec<Shaun>, greater:bool) -> Result<Shaun, String> {
+    let args = match args.len() {
+        1 => match args.into_iter().next() {
+            Some(Shaun::List(l)) => l,
+            Some(sn) => vec![sn],
+            None => Vec::new(),
+        },
+        _ => args,
+    };
+
+    let mut ret : Option<(Num, String)> = None;
+    for a in args {
+        match (a, ret.take()) {
+            (Shaun::Number(n, u), None) => ret = Some((n, u)),
+            (Shaun::Number(n, u), Some((m, v))) => {
+                let unit = unit(&u, &v)?.to_string();
+                let n = if (n > m) == greater { n } else { m };
+                ret = Some((n, unit));
+            },
+            (a, _) => return Err(format!("{}() expects numbers, found a {}", name, type_name(&a))),
+        }
+    }
+
+    ret.map(|(n, u)| Shaun::Number(n, u)).ok_or_else(|| format!("{}() expects at least one number", name))
+}
+
+impl Expr {
+    /// Reads an expression, nesting at most max_depth levels.
+    pub fn parse(text:&str, max_depth:usize) -> Result<Expr, ErrorKind> {
+        let mut parser = Parser { toks: tokenize(text).map_err(invalid)?, pos: 0, depth: 0, max_depth };
+        let ret = parser.or()?;
+        match parser.next() {
+            None => Ok(ret),
+            Some(_) => Err(invalid("unexpected trailing input")),
+        }
+    }
+
+    /// Evaluates the expression found at a position, if read from a
+    /// source; lookup gives the value referred to by a path, or None if
+    /// there is no such value.
+    pub fn eval<F>(&self, at:Option<Position>, lookup:&mut F) -> Result<Shaun, ParseError>
+        where F : FnMut(&[Segment]) -> Result<Option<Shaun>, ParseError> {
+        self.eval_with(lookup).map_err(|e| match (e, at) {
+            (EvalError::Parse(e), _) => e,
+            (EvalError::Message(m), Some(at)) => ParseError::new(ErrorKind::InvalidExpression(m), at),
+            (EvalError::Message(m), None) => ErrorKind::InvalidExpression(m).into(),
+        })
+    }
+
+    fn eval_with<F>(&self, lookup:&mut F) -> Result<Shaun, EvalError>
+        where F : FnMut(&[Segment]) -> Result<Option<Shaun>, ParseError> {
+        Ok(match *self {
+            Expr::Value(ref sn) => sn.clone(),
+            Expr::List(ref l) => Shaun::List(l.iter().map(|e| e.eval_with(lookup)).collect::<Result<_, _>>()?),
+            Expr::Ref(ref p) => match lookup(p).map_err(EvalError::Parse)? {
+                Some(sn) => sn,
+                None => return Err(EvalError::Message(format!("no value at `{}`", path::PathDisplay(p)))),
+            },
+            Expr::Neg(ref e) => match e.eval_with(lookup)? {
+                Shaun::Number(n, u) => Shaun::Number(number_op(Op::Sub, Num::UInt(0), n), u),
+                sn => return Err(EvalError::Message(format!("cannot negate a {}", type_name(&sn)))),
+            },
+            Expr::Not(ref e) => Shaun::Bool(!boolean(e.eval_with(lookup)?)?),
+            Expr::Binary(ref first, ref rest) => {
+                let mut ret = first.eval_with(lookup)?;
+                for &(op, ref e) in rest {
+                    ret = match op {
+                        // && and || only evaluate their right operand when needed
+                        Op::And => Shaun::Bool(boolean(ret)? && boolean(e.eval_with(lookup)?)?),
+                        Op::Or => Shaun::Bool(boolean(ret)? || boolean(e.eval_with(lookup)?)?),
+                        Op::Add | Op::Sub | Op::Mul | Op::Div | Op::Rem => arithmetic(op, ret, e.eval_with(lookup)?)?,
+                        _ => Shaun::Bool(comparison(op, &ret, &e.eval_with(lookup)?)?),
+                    };
+                }
+                ret
+            },
+            // the branch not taken is not evaluated
+            Expr::Call(ref f, ref args) if f == "if" => match args.len() {
+                3 if boolean(args[0].eval_with(lookup)?)? => args[1].eval_with(lookup)?,
+                3 => args[2].eval_with(lookup)?,
+                _ => return Err(EvalError::Message("if() expects 3 arguments".to_string())),
+            },
+            Expr::Call(ref f, ref args) => {
+                let args = args.iter().map(|e| e.eval_with(lookup)).collect::<Result<Vec<_>, _>>()?;
+                match f.as_ref() {
+                    "min" => extremum(f, args, false)?,
+                    "max" => extremum(f, args, true)?,
+                    "len" => match (args.len(), args.first()) {
+                        (1, Some(Shaun::String(s))) => Shaun::from(s.chars().count()),
+                        (1, Some(Shaun::List(l))) => Shaun::from(l.len()),
+                        (1, Some(Shaun::Object(o))) => Shaun::from(o.len()),
+                        _ => return Err(EvalError::Message("len() expects a string, list or object".to_string())),
+                    },
+                    _ => return Err(EvalError::Message(format!("unknown function `{}`", f))),
+                }
+            },
+        })
+    }
+}
+
+enum EvalError {
+    Parse(ParseError),
+    Message(String),
+}
+
+impl From<String> for EvalError {
+    fn from(m:String) -> EvalError {
+        EvalError::Message(m)
+    }
+}
+
+/// Evaluates an expression, such as `2 * timeout` or `prefix + "-svc"`,
+/// looking up the keys it refers to in an object.
+///
+/// Numbers keep their unit: adding or comparing numbers requires the same
+/// unit (or none), and a number without unit can scale any other.
+/// Strings are joined with `+`. The functions `min`, `max`, `len` and
+/// `if(condition, then, else)` are available.
+///
+/// # Examples
+/// ```
+/// use shaun::{evaluate, parse_str, Num, Shaun};
+///
+/// let sn = parse_str("workers: 4 timeout: 30 s");
+/// assert_eq!(evaluate("2 * timeout", &sn).unwrap(), Shaun::Number(Num::UInt(60), "s".to_string()));
+/// assert_eq!(evaluate("max(workers, 8)", &sn).unwrap(), Shaun::from(8.0));
+/// ```
+pub fn evaluate(text:&str, scope:&Shaun) -> Result<Shaun, ParseError> {
+    let expr = Expr::parse(text, MAX_NESTING).map_err(ParseError::from)?;
+    expr.eval(None, &mut |p: &[Segment]| Ok(path::get(scope, p).cloned()))
+}
@@ -0,0 +1,231 @@
+use shaun_type::Shaun;
+use error::{ErrorKind, ParseError, Position};
+use parser::{parse_with, Hooks};
+use reader::{Event, Reader};
+
+use std::collections::HashMap;
+use std::env;
+use std::fs::File;
+use std::io;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+
+/// Gives the values of variables, as in `${name}` or `${scope:name}`.
+pub trait Resolver {
+    fn resolve(&self, scope:Option<&str>, name:&str) -> Option<String>;
+}
+
+/// Resolves variables from the environment, as in `${HOME}` or
+/// `${env:PORT}`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Env;
+
+impl Resolver for Env {
+    fn resolve(&self, scope:Option<&str>, name:&str) -> Option<String> {
+        match scope {
+            None | Some("env") => env::var(name).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// A map resolves variables of any scope by their name.
+impl Resolver for HashMap<String, String> {
+    fn resolve(&self, _scope:Option<&str>, name:&str) -> Option<String> {
+        self.get(name).cloned()
+    }
+}
+
+/// Several resolvers are asked in order, the first answer wins.
+impl Resolver for Vec<Box<dyn Resolver>> {
+    fn resolve(&self, scope:Option<&str>, name:&str) -> Option<String> {
+        self.iter().filter_map(|r| r.resolve(scope, name)).next()
+    }
+}
+
+impl<T : Resolver + ?Sized> Resolver for &T {
+    fn resolve(&self, scope:Option<&str>, name:&str) -> Option<String> {
+        (**self).resolve(scope, name)
+    }
+}
+
+/// Resolves variables from a secrets file, holding `NAME=value` lines,
+/// as in `${secret:DB_PASSWORD}`. Blank lines and lines starting with
+/// `#` are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct Secrets {
+    values : HashMap<String, String>,
+}
+
+impl Secrets {
+    pub fn from_file(path:&Path) -> io::Result<Secrets> {
+        Secrets::from_reader(File::open(path)?)
+    }
+
+    pub fn from_reader<R : Read>(reader:R) -> io::Result<Secrets> {
+        let mut values = HashMap::new();
+        for line in BufReader::new(reader).lines() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') { continue }
+            if let Some(i) = line.find('=') {
+                values.insert(line[..i].trim().to_string(), line[i+1..].trim().to_string());
+            }
+        }
+        Ok(Secrets { values })
+    }
+}
+
+impl Resolver for Secrets {
+    fn resolve(&self, scope:Option<&str>, name:&str) -> Option<String> {
+        match scope {
+            None | Some("secret") => self.values.get(name).cloned(),
+            _ => None,
+        }
+    }
+}
+
+/// Substitutes variables in SHAUN data.
+///
+/// Variables are written `${name}`, `${scope:name}`, and may have a
+/// default value, as in `${env:PORT:-8080}`. Within strings, they are
+/// replaced by their text (`$${` stands for a literal `${`). Written as a
+/// value while parsing, they are replaced by the SHAUN value their text
+/// holds, so that `port: ${env:PORT}` gives a number.
+///
+/// Unknown variables without default are errors in strict mode, and are
+/// left as they are written otherwise.
+///
+/// # Examples
+/// ```
+/// use shaun::{Interpolator, Shaun};
+/// use std::collections::HashMap;
+///
+/// let mut vars = HashMap::new();
+/// vars.insert("HOME".to_string(), "/home/jar".to_string());
+///
+/// let sn = Interpolator::new(vars)
+///     .parse("dir: \"${HOME}/data\" port: ${env:PORT:-8080}".as_bytes())
+///     .unwrap();
+/// assert_eq!(sn.get("dir").unwrap(), &Shaun::from("/home/jar/data"));
+/// assert_eq!(sn.get("port").unwrap(), &Shaun::from(8080.0));
+/// ```
+pub struct Interpolator<V : Resolver> {
+    resolver : V,
+    strict : bool,
+}
+
+impl<V : Resolver> Interpolator<V> {
+    pub fn new(resolver:V) -> Interpolator<V> {
+        Interpolator { resolver, strict: false }
+    }
+
+    pub fn strict(mut self, strict:bool) -> Interpolator<V> {
+        self.strict = strict;
+        self
+    }
+
+    /// Parses the content of a reader, substituting its variables.
+    pub fn parse<R : Read>(&self, reader:R) -> Result<Shaun, ParseError> {
+        parse_with(reader, &mut InterpolatorHooks(self))?.resolve()
+    }
+
+    /// Substitutes the variables of every string of an already parsed value.
+    pub fn interpolate(&self, sn:Shaun) -> Result<Shaun, ParseError> {
+        match sn {
+            Shaun::String(s) => Ok(Shaun::String(self.expand(&s)?)),
+            Shaun::List(l) => l.into_iter().map(|v| self.interpolate(v)).collect::<Result<_, _>>().map(Shaun::List),
+            Shaun::Object(o) => o.into_iter()
+                .map(|(k, v)| self.interpolate(v).map(|v| (k, v)))
+                .collect::<Result<_, _>>().map(Shaun::Object),
+            _ => Ok(sn),
+        }
+    }
+
+    /// Substitutes the variables of a string.
+    pub fn expand(&self, s:&str) -> Result<String, ParseError> {
+        let mut ret = String::new();
+        let mut rest = s;
+
+        while let Some(i) = rest.find("${") {
+            if rest[..i].ends_with('$') {
+                ret.push_str(&rest[..i-1]);
+                ret.push_str("${");
+                rest = &rest[i+2..];
+                continue
+            }
+            ret.push_str(&rest[..i]);
+            match rest[i..].find('}') {
+                None => { rest = &rest[i..]; break },
+                Some(end) => {
+                    let var = &rest[i+2..i+end];
+                    match self.lookup(var)? {
+                        Some(v) => ret.push_str(&v),
+                        None => ret.push_str(&rest[i..i+end+1]),
+                    }
+                    rest = &rest[i+end+1..];
+                },
+            }
+        }
+
+        ret.push_str(rest);
+        Ok(ret)
+    }
+
+    /*
+     * lookup() gives the text of a variable written "scope:name:-default",
+     * None if it is unknown (in lenient mode)
+     */
+    fn lookup(&self, var:&str) -> Result<Option<String>, ParseError> {
+        let (var, default) = match var.find(":-") {
+            Some(i) => (&var[..i], Some(&var[i+2..])),
+            None => (var, None),
+        };
+        let (scope, name) = match var.find(':') {
+            Some(i) => (Some(var[..i].trim()), var[i+1..].trim()),
+            None => (None, var.trim()),
+        };
+
+        match self.resolver.resolve(scope, name).or_else(|| default.map(|d| d.to_string())) {
+            Some(v) => Ok(Some(v)),
+            None if self.strict => Err(ErrorKind::UnknownVariable(var.to_string()).into()),
+            None => Ok(None),
+        }
+    }
+}
+
+/*
+ * typed() reads the text of a variable as a single SHAUN value, or as
+ * a string when it is anything else
+ */
+fn typed(text:&str) -> Shaun {
+    let source = format!("v: {}", text);
+    let events : Result<Vec<Event>, ParseError> = Reader::new(source.as_bytes()).collect();
+    match events {
+        Ok(mut e) if e.len() == 4 => match e.swap_remove(2) {
+            Event::Null => Shaun::Null,
+            Event::Bool(b) => Shaun::Bool(b),
+            Event::Number(n, u) => Shaun::Number(n, u),
+            Event::DateTime(d) => Shaun::DateTime(d),
+            Event::String(s) => Shaun::String(s),
+            _ => Shaun::String(text.to_string()),
+        },
+        _ => Shaun::String(text.to_string()),
+    }
+}
+
+struct InterpolatorHooks<'a, V : Resolver + 'a>(&'a Interpolator<V>);
+
+impl<'a, V : Resolver> Hooks for InterpolatorHooks<'a, V> {
+    fn string(&mut self, s:String, at:Position) -> Result<Shaun, ParseError> {
+        self.0.expand(&s).map(Shaun::String).map_err(|e| ParseError::new(e.into_kind(), at))
+    }
+
+    fn variable(&mut self, name:&str, at:Position) -> Result<Shaun, ParseError> {
+        match self.0.lookup(name) {
+            Ok(Some(text)) => Ok(typed(&text)),
+            Ok(None) => Ok(Shaun::String(format!("${{{}}}", name))),
+            Err(e) => Err(ParseError::new(e.into_kind(), at)),
+        }
+    }
+}
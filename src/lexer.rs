@@ -0,0 +1,1091 @@
+use error::{ErrorKind, ParseError, Position};
+use number::Num;
+use options::Limit;
+use datetime::DateTime;
+#[cfg(feature = "decimal")]
+use number::Decimal;
+
+use std::fmt;
+use std::collections::VecDeque;
+use std::io::{Bytes, BufReader, Read};
+
+#[derive(Debug, PartialEq)]
+pub enum ParseToken {
+    Key(char),
+    Name(String),
+    Id(String),
+    Atom(ParseAtom),
+    Comment(String),
+    Separator,
+    Anchor(String),
+    Alias(String),
+    Variable(String),
+    Expression(String),
+    Include,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseAtom {
+    String(String),
+    Bool(bool),
+    Number(Num),
+    DateTime(DateTime),
+    Null,
+}
+
+impl fmt::Display for ParseToken {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseToken::Key(c) => write!(f, "`{}`", c),
+            ParseToken::Name(ref n) => write!(f, "key `{}`", n),
+            ParseToken::Id(ref i) => write!(f, "identifier `{}`", i),
+            ParseToken::Atom(ParseAtom::String(_)) => write!(f, "string"),
+            ParseToken::Atom(ParseAtom::Bool(b)) => write!(f, "`{}`", b),
+            ParseToken::Atom(ParseAtom::Number(_)) => write!(f, "number"),
+            ParseToken::Atom(ParseAtom::DateTime(ref d)) => write!(f, "date `{}`", d),
+            ParseToken::Atom(ParseAtom::Null) => write!(f, "`null`"),
+            ParseToken::Comment(_) => write!(f, "comment"),
+            ParseToken::Separator => write!(f, "document separator `---`"),
+            ParseToken::Anchor(ref a) => write!(f, "anchor `&{}`", a),
+            ParseToken::Alias(ref a) => write!(f, "alias `*{}`", a),
+            ParseToken::Variable(ref v) => write!(f, "variable `${{{}}}`", v),
+            ParseToken::Expression(ref e) => write!(f, "expression `$({})`", e),
+            ParseToken::Include => write!(f, "`@include`"),
+        }
+    }
+}
+
+/*
+ * Chars decodes UTF-8 from any reader, one char at a time, and keeps
+ * track of the position of the next char. It also counts the bytes read
+ * in all, and those of the chars consumed by the current token, to
+ * enforce limits.
+ */
+struct Chars<R : Read> {
+    bytes : Bytes<BufReader<R>>,
+    peeked : VecDeque<char>,
+    position : Position,
+    read : u64,
+    max_read : Option<u64>,
+    token : u64,
+    token_start : Position,
+    in_token : bool,
+    max_token : Option<u64>,
+}
+
+impl<R : Read> Chars<R> {
+    fn new(reader:R) -> Chars<R> {
+        Chars {
+            bytes: BufReader::new(reader).bytes(),
+            peeked: VecDeque::new(),
+            position: Position::start(),
+            read: 0,
+            max_read: None,
+            token: 0,
+            token_start: Position::start(),
+            in_token: false,
+            max_token: None,
+        }
+    }
+
+    fn start_token(&mut self) {
+        self.token = 0;
+        self.token_start = self.position;
+        self.in_token = true;
+    }
+
+    /*
+     * end_token() stops counting the chars of the token, such as the ':'
+     * following a key
+     */
+    fn end_token(&mut self) {
+        self.in_token = false;
+    }
+
+    fn error(&self, kind:ErrorKind) -> ParseError {
+        ParseError::new(kind, self.position)
+    }
+
+    fn byte(&mut self) -> Result<Option<u8>, ParseError> {
+        match self.bytes.next() {
+            None => Ok(None),
+            Some(Ok(b)) => {
+                self.read += 1;
+                match self.max_read {
+                    Some(max) if self.read > max => Err(self.error(ErrorKind::LimitExceeded(Limit::InputSize, max))),
+                    _ => Ok(Some(b)),
+                }
+            },
+            Some(Err(e)) => Err(self.error(ErrorKind::Io(e))),
+        }
+    }
+
+    fn decode(&mut self) -> Result<Option<char>, ParseError> {
+        let first = match self.byte()? {
+            None => return Ok(None),
+            Some(b) => b,
+        };
+
+        let width = match first {
+            0x00..=0x7F => return Ok(Some(first as char)),
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => return Err(self.error(ErrorKind::InvalidUtf8)),
+        };
+
+        let mut buf = [first, 0, 0, 0];
+        for b in buf.iter_mut().take(width).skip(1) {
+            *b = self.byte()?.ok_or_else(|| self.error(ErrorKind::InvalidUtf8))?;
+        }
+
+        match ::std::str::from_utf8(&buf[..width]) {
+            Ok(s) => Ok(s.chars().next()),
+            Err(_) => Err(self.error(ErrorKind::InvalidUtf8)),
+        }
+    }
+
+    fn peek(&mut self) -> Result<Option<char>, ParseError> {
+        self.peek_nth(0)
+    }
+
+    /*
+     * peek_nth() looks n chars past the next one, such as the rest of a
+     * date after its year
+     */
+    fn peek_nth(&mut self, n:usize) -> Result<Option<char>, ParseError> {
+        while self.peeked.len() <= n {
+            match self.decode()? {
+                Some(c) => self.peeked.push_back(c),
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(self.peeked[n]))
+    }
+
+    /*
+     * follows() tells whether the next chars have a shape, where '9'
+     * stands for any digit
+     */
+    fn follows(&mut self, shape:&str) -> Result<bool, ParseError> {
+        for (i, s) in shape.chars().enumerate() {
+            match self.peek_nth(i)? {
+                Some(c) if c == s || (s == '9' && c.is_ascii_digit()) => (),
+                _ => return Ok(false),
+            }
+        }
+        Ok(true)
+    }
+
+    fn next(&mut self) -> Result<Option<char>, ParseError> {
+        let c = self.peek()?;
+        self.peeked.pop_front();
+        if let (Some(c), true) = (c, self.in_token) {
+            self.token += c.len_utf8() as u64;
+            match self.max_token {
+                Some(max) if self.token > max => {
+                    return Err(ParseError::new(ErrorKind::LimitExceeded(Limit::StringLength, max), self.token_start))
+                },
+                _ => (),
+            }
+        }
+        match c {
+            Some('\n') => { self.position.line += 1; self.position.column = 1 },
+            Some(_) => self.position.column += 1,
+            None => (),
+        }
+        Ok(c)
+    }
+}
+
+/*
+ * Helper functions, mostly for testing characters
+ */
+
+fn join(lines:Vec<(String, usize)>) -> String {
+    lines.into_iter().map(|l| l.0).collect::<Vec<_>>().join("\n")
+}
+
+fn is_kwd(c:char) -> bool {
+    c == ':' ||
+    c == '{' ||
+    c == '}' ||
+    c == '[' ||
+    c == ']'
+}
+
+fn is_id_begin(c:char) -> bool {
+    c.is_alphabetic() || c == '_'
+}
+
+fn is_comment_begin(c:char) -> bool {
+    c == '/' || c == '#' || c == '('
+}
+
+fn is_double_begin(c:char) -> bool {
+    c.is_ascii_digit() || c == '.' || c == '-' || c == '+'
+}
+
+fn is_id_char(c:char) -> bool {
+    c.is_ascii_digit() || is_id_begin(c)
+}
+
+fn is_key_char(c:char) -> bool {
+    is_id_char(c) || c == '-' || c == '.'
+}
+
+/// Whether a key can be written without quotes.
+pub fn is_bare_key(key:&str) -> bool {
+    let mut chars = key.chars();
+    chars.next().is_some_and(is_id_begin) && chars.all(is_key_char)
+}
+
+/*
+ * special_float() is the value of the words standing for floats, with or
+ * without a sign
+ */
+fn special_float(id:&str) -> Option<f64> {
+    match id {
+        "inf" => Some(f64::INFINITY),
+        "nan" => Some(f64::NAN),
+        _ => None,
+    }
+}
+
+/*
+ * digits() removes the '_' separating the digits of a number, None if
+ * one is not between two digits
+ */
+fn digits(s:&str, radix:u32) -> Option<String> {
+    let chars : Vec<char> = s.chars().collect();
+    let separated = |i:usize| i > 0 && i + 1 < chars.len()
+        && chars[i - 1].is_digit(radix) && chars[i + 1].is_digit(radix);
+    if s.is_empty() || !(0..chars.len()).all(|i| chars[i] != '_' || separated(i)) { return None }
+    Some(s.replace('_', ""))
+}
+
+fn parse_integer(magnitude:Option<u64>, negative:bool) -> Option<Num> {
+    match magnitude {
+        Some(m) if !negative => Some(Num::UInt(m)),
+        Some(m) if m <= i64::MAX as u64 + 1 => Some(Num::Int((-(m as i128)) as i64)),
+        _ => None,
+    }
+}
+
+/*
+ * parse_number() gives the value of the text of a number, without its
+ * sign, None if it is not valid
+ */
+fn parse_number(text:&str, radix:u32, negative:bool) -> Option<Num> {
+    let d = digits(text, radix)?;
+    if radix != 10 {
+        return parse_integer(u64::from_str_radix(&d, radix).ok(), negative)
+    }
+
+    let float = |d:&str| d.parse::<f64>().ok().map(|f| Num::Float(if negative { -f } else { f }));
+    if d.contains(['.', 'e', 'E']) { return float(&d) }
+    // integers beyond 64 bits are kept as floats
+    parse_integer(d.parse().ok(), negative).or_else(|| float(&d))
+}
+
+/// Splits a stream of chars into tokens, without ever holding more than
+/// the token being read (and those peeked or put back) in memory.
+pub struct Lexer<R : Read> {
+    chars : Chars<R>,
+    peeked : Vec<Option<(ParseToken, Position)>>,
+    quoteless : bool,
+    strict : bool,
+    commas : bool,
+    after_comma : bool,
+}
+
+impl<R : Read> Lexer<R> {
+    pub fn new(reader:R) -> Lexer<R> {
+        Lexer { chars: Chars::new(reader), peeked: Vec::new(), quoteless: false, strict: false, commas: true, after_comma: false }
+    }
+
+    /// Reads the next token, unless already peeked, as an HJSON value:
+    /// anything but a number, a keyword or a delimited value is a string
+    /// running to the end of the line.
+    pub fn quoteless(&mut self) {
+        self.quoteless = true;
+    }
+
+    /// Rejects unknown chars and escapes in strings, instead of skipping
+    /// the former and reading the escaped char of the latter as is.
+    pub fn strict(&mut self) {
+        self.strict = true;
+    }
+
+    /// Fails once more than `max` bytes are read.
+    pub fn max_input_size(&mut self, max:u64) {
+        self.chars.max_read = Some(max);
+    }
+
+    /// Fails on tokens, such as strings, keys or comments, longer than
+    /// `max` bytes.
+    pub fn max_string_length(&mut self, max:u64) {
+        self.chars.max_token = Some(max);
+    }
+
+    /// Rejects commas, which otherwise may separate values.
+    pub fn no_commas(&mut self) {
+        self.commas = false;
+    }
+
+    /*
+     * stray() is called on chars which are not part of SHAUN, that only
+     * strict lexers reject
+     */
+    fn stray(&self, c:char, at:Position) -> Result<(), ParseError> {
+        if !self.strict { return Ok(()) }
+        Err(ParseError::new(ErrorKind::UnexpectedToken(format!("character `{}`", c)), at))
+    }
+
+    /// Position of the next char to be read.
+    pub fn current_position(&self) -> Position {
+        self.chars.position
+    }
+
+    pub fn peek(&mut self) -> Result<Option<&ParseToken>, ParseError> {
+        if self.peeked.is_empty() {
+            let t = self.lex()?;
+            self.peeked.push(t);
+        }
+        Ok(self.peeked.last().and_then(|t| t.as_ref()).map(|t| &t.0))
+    }
+
+    /// Position of the next token, or of the end of the stream.
+    pub fn next_position(&mut self) -> Result<Position, ParseError> {
+        self.peek()?;
+        match self.peeked.last() {
+            Some(&Some((_, pos))) => Ok(pos),
+            _ => Ok(self.current_position()),
+        }
+    }
+
+    pub fn next(&mut self) -> Result<Option<(ParseToken, Position)>, ParseError> {
+        match self.peeked.pop() {
+            Some(t) => Ok(t),
+            None => self.lex(),
+        }
+    }
+
+    /// Puts back a token read, to be read again. Tokens put back are read
+    /// in the reverse order.
+    pub fn push_back(&mut self, token:ParseToken, at:Position) {
+        self.peeked.push(Some((token, at)));
+    }
+
+    /// Whether a comma came before the last token lexed, which is the
+    /// peeked one, if any.
+    pub fn after_comma(&self) -> bool {
+        self.after_comma
+    }
+
+    /*
+     * lex_<x> functions consume the stream of char and return a value
+     * depending on the object we are "lexing"
+     *
+     * every lex_<x> function must consume the stream to the caracter just after
+     * the read symbol.
+     *
+     * "10 years" -> lex_number() -> "10 years"
+     *  ^                               ^
+     *  before                        after
+     */
+    fn lex_number(&mut self) -> Result<ParseToken, ParseError> {
+        let start = self.current_position();
+        let (text, value) = self.read_number()?;
+
+        // "---" is not a number but separates documents, on a line of its own
+        if text == "---" && start.column == 1 && self.at_line_end()? { return Ok(ParseToken::Separator) }
+
+        // dates (2026-10-18) and times (12:00) start like numbers
+        if text.bytes().all(|b| b.is_ascii_digit())
+            && ((text.len() == 4 && self.chars.follows("-99-99")?) || (text.len() == 2 && self.chars.follows(":99")?)) {
+            return self.lex_datetime(text, start)
+        }
+
+        match value {
+            Some(n) => Ok(ParseToken::Atom(ParseAtom::Number(n))),
+            None => Err(ParseError::new(ErrorKind::InvalidNumber(text), start)),
+        }
+    }
+
+    /*
+     * at_line_end() tells whether only blanks are left on the line
+     */
+    fn at_line_end(&mut self) -> Result<bool, ParseError> {
+        let mut n = 0;
+        loop {
+            match self.chars.peek_nth(n)? {
+                None | Some('\n') | Some('\r') => return Ok(true),
+                Some(' ') | Some('\t') => n += 1,
+                Some(_) => return Ok(false),
+            }
+        }
+    }
+
+    /*
+     * Reads the rest of a date or time, whose first digits were read as a
+     * number
+     */
+    fn lex_datetime(&mut self, mut text:String, start:Position) -> Result<ParseToken, ParseError> {
+        while let Some(c) = self.chars.peek()? {
+            if !c.is_ascii_digit() && !matches!(c, '-' | ':' | '.' | '+' | 'T' | 't' | 'Z' | 'z') { break }
+            text.push(c);
+            self.chars.next()?;
+        }
+
+        match DateTime::parse(&text) {
+            Some(d) => Ok(ParseToken::Atom(ParseAtom::DateTime(d))),
+            None => Err(ParseError::new(ErrorKind::InvalidDateTime(text), start)),
+        }
+    }
+
+    /*
+     * Reads the text of a number along with its value, None if it is not
+     * valid. Numbers are decimal, hexadecimal (0x), octal (0o) or binary
+     * (0b) integers, floats, inf or nan, with an optional sign, and may
+     * have their digits separated by '_'.
+     */
+    fn read_number(&mut self) -> Result<(String, Option<Num>), ParseError> {
+        let mut text = String::new();
+        while let Some(c) = self.chars.peek()? {
+            if c != '+' && c != '-' { break }
+            text.push(c);
+            self.chars.next()?;
+        }
+        if text.len() > 1 { return Ok((text, None)) }
+        let negative = text == "-";
+
+        if let Some(c) = self.chars.peek()? {
+            if c.is_alphabetic() {
+                let id = self.lex_id()?;
+                text.push_str(&id);
+                return Ok((text, special_float(&id).map(|v| Num::Float(if negative { -v } else { v }))))
+            }
+        }
+
+        let sign = text.len();
+        let mut radix = 10;
+        if self.chars.peek()? == Some('0') {
+            text.push('0');
+            self.chars.next()?;
+            if let Some(c) = self.chars.peek()? {
+                radix = match c { 'x' => 16, 'o' => 8, 'b' => 2, _ => 10 };
+                if radix != 10 {
+                    text.push(c);
+                    self.chars.next()?;
+                }
+            }
+        }
+        let prefix = if radix == 10 { sign } else { sign + 2 };
+
+        while let Some(c) = self.chars.peek()? {
+            let valid = if radix != 10 { c.is_ascii_alphanumeric() || c == '_' }
+                else {
+                    c.is_ascii_digit() || c == '_' || c == '.' || c == 'e' || c == 'E'
+                        || ((c == '+' || c == '-') && text.ends_with(['e', 'E']))
+                };
+            if !valid { break }
+            text.push(c);
+            self.chars.next()?;
+        }
+
+        let value = parse_number(&text[prefix..], radix, negative);
+
+        // decimals keep their text, unless they are integers of 64 bits
+        #[cfg(feature = "decimal")]
+        let value = match value {
+            Some(Num::Float(_)) if radix == 10 => Decimal::parse(&text).map(Num::Decimal),
+            v => v,
+        };
+
+        Ok((text, value))
+    }
+
+    fn lex_id(&mut self) -> Result<String, ParseError> {
+        let mut buffer = String::new();
+
+        while let Some(c) = self.chars.peek()? {
+            if is_id_char(c) { buffer.push(c) }
+            else { break }
+
+            self.chars.next()?;
+        }
+
+        Ok(buffer)
+    }
+
+    /*
+     * Returns None when the '/' does not start a comment.
+     */
+    fn lex_comment(&mut self) -> Result<Option<String>, ParseError> {
+        let start = self.current_position();
+        let end = match self.chars.next()? {
+            Some('/') => match self.chars.peek()? {
+                Some('*') => { self.chars.next()?; "*/" },
+                Some('/') => { self.chars.next()?; "\n" },
+                _ => return Ok(None),
+            },
+            Some('(') => ")",
+            _ => "\n",
+        };
+
+        let mut buffer = String::new();
+        loop {
+            match self.chars.next()? {
+                None => {
+                    if end == "\n" { break }
+                    return Err(ParseError::new(ErrorKind::UnexpectedEof, start))
+                },
+                Some('*') if end == "*/" && self.chars.peek()? == Some('/') => {
+                    self.chars.next()?;
+                    break
+                },
+                Some(c) if end.len() == 1 && end.starts_with(c) => break,
+                Some(c) => buffer.push(c),
+            }
+        }
+
+        Ok(Some(buffer))
+    }
+
+    /*
+     * Reads a double quoted string, which may span several lines: a line
+     * break right after the opening quote is dropped, and the indentation
+     * of the closing quote, when alone on its line, is stripped from every
+     * line. Otherwise lines are stripped up to the column of the opening
+     * quote. Folded strings (`>"..."`) then have their line breaks turned
+     * into spaces, empty lines standing for line breaks.
+     */
+    fn lex_string(&mut self, folded:bool) -> Result<String, ParseError> {
+        let start = self.current_position();
+        self.chars.next()?;
+
+        let mut lines = self.lex_quoted('"', start)?;
+        if lines.len() > 1 {
+            let dropped = lines[0].0.is_empty();
+            if dropped { lines.remove(0); }
+
+            let closing = lines.last().filter(|l| l.1 == l.0.len()).map(|l| l.1);
+            let strip = match closing {
+                Some(n) => { lines.pop(); n },
+                None => start.column as usize - if dropped { 1 } else { 0 },
+            };
+            for (i, l) in lines.iter_mut().enumerate() {
+                if i == 0 && !dropped { continue }
+                let mut n = l.1.min(strip);
+                while !l.0.is_char_boundary(n) { n -= 1 }
+                l.0.drain(..n);
+            }
+        }
+
+        if !folded { return Ok(join(lines)) }
+        let mut buffer = String::new();
+        let mut joined = false;
+        for (l, _) in lines {
+            if l.is_empty() { buffer.push('\n') }
+            else if joined { buffer.push(' ') }
+            joined = !l.is_empty();
+            buffer.push_str(&l);
+        }
+        Ok(buffer)
+    }
+
+    /*
+     * Reads the lines of a string up to its closing quote, the opening
+     * one being already read. Line breaks are read as '\n', even when
+     * written "\r\n", and every line comes with the length of the
+     * whitespace it starts with, escapes aside.
+     */
+    fn lex_quoted(&mut self, quote:char, start:Position) -> Result<Vec<(String, usize)>, ParseError> {
+        let mut lines = vec![(String::new(), 0)];
+        let mut indenting = true;
+        // the string is read up to its closing quote despite invalid
+        // escapes, so that reading can go on after it
+        let mut invalid = None;
+
+        loop {
+            let at = self.current_position();
+            let c = match self.chars.next()? {
+                None => return Err(ParseError::new(ErrorKind::UnexpectedEof, start)),
+                Some(c) if c == quote => break,
+                Some(c) => c,
+            };
+            match c {
+                '\\' => {
+                    match self.lex_escape(at, start) {
+                        Ok(escaped) => lines.last_mut().unwrap().0.push_str(&escaped),
+                        Err(e) if matches!(e.kind(), ErrorKind::InvalidEscape(_)) => { invalid.get_or_insert(e); },
+                        Err(e) => return Err(e),
+                    }
+                    indenting = false;
+                },
+                '\r' if self.chars.peek()? == Some('\n') => (),
+                '\n' => {
+                    lines.push((String::new(), 0));
+                    indenting = true;
+                },
+                _ => {
+                    let line = lines.last_mut().unwrap();
+                    indenting = indenting && c.is_whitespace();
+                    if indenting { line.1 += c.len_utf8() }
+                    line.0.push(c);
+                },
+            }
+        }
+
+        match invalid {
+            Some(e) => Err(e),
+            None => Ok(lines),
+        }
+    }
+
+    /*
+     * Reads what follows a '\\' in a string, giving the text it stands for
+     */
+    fn lex_escape(&mut self, at:Position, start:Position) -> Result<String, ParseError> {
+        let c = self.chars.next()?
+            .ok_or_else(|| ParseError::new(ErrorKind::UnexpectedEof, start))?;
+        let ret = match c {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            '\\' | '"' | '\'' | '/' => c,
+            'u' => match self.lex_unicode()? {
+                (_, Some(c)) => c,
+                (text, None) if self.strict => {
+                    return Err(ParseError::new(ErrorKind::InvalidEscape(format!("\\u{}", text)), at))
+                },
+                (text, None) => return Ok(format!("u{}", text)),
+            },
+            _ if self.strict => {
+                return Err(ParseError::new(ErrorKind::InvalidEscape(format!("\\{}", c)), at))
+            },
+            _ => c,
+        };
+        Ok(ret.to_string())
+    }
+
+    /*
+     * Reads the code of a `\u` escape, either `\u{1F600}` or `\uXXXX`,
+     * surrogate pairs being written as two escapes (`\uD83D\uDE00`).
+     * Returns the text read along with the char, None if invalid.
+     */
+    fn lex_unicode(&mut self) -> Result<(String, Option<char>), ParseError> {
+        let mut text = String::new();
+
+        if self.chars.peek()? == Some('{') {
+            loop {
+                match self.chars.peek()? {
+                    Some(c) if (c == '{' && text.is_empty()) || (c.is_ascii_hexdigit() && text.len() < 7) => text.push(c),
+                    Some('}') => text.push('}'),
+                    _ => return Ok((text, None)),
+                }
+                self.chars.next()?;
+                if text.ends_with('}') { break }
+            }
+            let code = u32::from_str_radix(&text[1..text.len()-1], 16).ok();
+            return Ok((text, code.and_then(char::from_u32)))
+        }
+
+        let code = match self.lex_hex4(&mut text)? {
+            Some(c) => c,
+            None => return Ok((text, None)),
+        };
+        if !(0xD800..0xDC00).contains(&code) {
+            return Ok((text, char::from_u32(code)))
+        }
+
+        // a high surrogate is followed by the escape of a low one
+        for c in ['\\', 'u'] {
+            if self.chars.peek()? != Some(c) { return Ok((text, None)) }
+            text.push(c);
+            self.chars.next()?;
+        }
+        let ret = match self.lex_hex4(&mut text)? {
+            Some(low) if (0xDC00..0xE000).contains(&low) => char::from_u32(0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00)),
+            _ => None,
+        };
+        Ok((text, ret))
+    }
+
+    fn lex_hex4(&mut self, text:&mut String) -> Result<Option<u32>, ParseError> {
+        let begin = text.len();
+        for _ in 0..4 {
+            match self.chars.peek()? {
+                Some(c) if c.is_ascii_hexdigit() => text.push(c),
+                _ => return Ok(None),
+            }
+            self.chars.next()?;
+        }
+        Ok(u32::from_str_radix(&text[begin..], 16).ok())
+    }
+
+    /*
+     * Reads a raw string, whose content is kept as is, the leading `r`
+     * being already read: `r"C:\path"`, or `r#"say "hi""#` with as many
+     * '#' around the quotes as needed.
+     */
+    fn lex_raw(&mut self, start:Position) -> Result<String, ParseError> {
+        let mut hashes = 0;
+        while self.chars.peek()? == Some('#') {
+            hashes += 1;
+            self.chars.next()?;
+        }
+        match self.chars.next()? {
+            Some('"') => (),
+            Some(c) => return Err(ParseError::new(ErrorKind::UnexpectedToken(format!("`{}`", c)), start)),
+            None => return Err(ParseError::new(ErrorKind::UnexpectedEof, start)),
+        }
+
+        let mut buffer = String::new();
+        loop {
+            match self.chars.next()? {
+                None => return Err(ParseError::new(ErrorKind::UnexpectedEof, start)),
+                Some('"') => {
+                    let mut closing = 0;
+                    while closing < hashes && self.chars.peek()? == Some('#') {
+                        closing += 1;
+                        self.chars.next()?;
+                    }
+                    if closing == hashes { break }
+                    buffer.push('"');
+                    buffer.push_str(&"#".repeat(closing));
+                },
+                Some(c) => buffer.push(c),
+            }
+        }
+
+        Ok(buffer)
+    }
+
+    /*
+     * Reads a ''' string, the opening quotes being already read. Its lines
+     * lose the indentation of the opening quotes, and blank first and last
+     * lines are dropped. Nothing is escaped.
+     */
+    fn lex_multiline(&mut self, start:Position) -> Result<String, ParseError> {
+        let mut raw = String::new();
+        let mut quotes = 0;
+
+        while quotes < 3 {
+            match self.chars.next()? {
+                None => return Err(ParseError::new(ErrorKind::UnexpectedEof, start)),
+                Some('\'') => quotes += 1,
+                Some(c) => {
+                    for _ in 0..quotes { raw.push('\'') }
+                    quotes = 0;
+                    raw.push(c);
+                },
+            }
+        }
+
+        let raw = raw.replace("\r\n", "\n");
+        let indent = start.column as usize - 1;
+        let mut lines : Vec<&str> = raw.split('\n').collect();
+        if lines.len() > 1 && lines[0].trim().is_empty() { lines.remove(0); }
+        if lines.len() > 1 && lines[lines.len() - 1].trim().is_empty() { lines.pop(); }
+
+        let lines : Vec<&str> = lines.into_iter().map(|l| {
+            let n = l.char_indices().take(indent)
+                .take_while(|&(_, c)| c.is_whitespace())
+                .last().map_or(0, |(i, c)| i + c.len_utf8());
+            &l[n..]
+        }).collect();
+        Ok(lines.join("\n"))
+    }
+
+    /*
+     * Reads an HJSON value which is not delimited: a number or a keyword
+     * if nothing but a delimiter or a comment follows it on its line, or
+     * else a string running to the end of the line.
+     */
+    fn lex_quoteless(&mut self) -> Result<ParseToken, ParseError> {
+        let (mut buffer, literal) = if self.chars.peek()?.is_some_and(is_double_begin) {
+            let (text, value) = self.read_number()?;
+            (text, value.map(|n| ParseToken::Atom(ParseAtom::Number(n))))
+        }
+        else {
+            let id = self.lex_id()?;
+            let literal = match id.as_ref() {
+                "true" => Some(ParseToken::Atom(ParseAtom::Bool(true))),
+                "false" => Some(ParseToken::Atom(ParseAtom::Bool(false))),
+                "null" => Some(ParseToken::Atom(ParseAtom::Null)),
+                _ => None,
+            };
+            (id, literal)
+        };
+
+        let mut spaces = String::new();
+        while let Some(c) = self.chars.peek()? {
+            if c != ' ' && c != '\t' { break }
+            spaces.push(c);
+            self.chars.next()?;
+        }
+
+        if let Some(tok) = literal {
+            match self.chars.peek()? {
+                None | Some('\n') | Some('\r') | Some(',') | Some('}') | Some(']') | Some('#') | Some('/') => return Ok(tok),
+                _ => (),
+            }
+        }
+
+        buffer.push_str(&spaces);
+        while let Some(c) = self.chars.peek()? {
+            if c == '\n' { break }
+            buffer.push(c);
+            self.chars.next()?;
+        }
+
+        Ok(ParseToken::Atom(ParseAtom::String(buffer.trim_end().to_string())))
+    }
+
+    /*
+     * Reads the text of an expression up to its closing ')', skipping
+     * nested parentheses and those within strings.
+     */
+    fn lex_expression(&mut self, start:Position) -> Result<String, ParseError> {
+        let mut buffer = String::new();
+        let mut depth = 0;
+        let mut in_string = false;
+
+        loop {
+            let c = self.chars.next()?
+                .ok_or_else(|| ParseError::new(ErrorKind::UnexpectedEof, start))?;
+            match c {
+                ')' if !in_string && depth == 0 => return Ok(buffer),
+                ')' if !in_string => depth -= 1,
+                '(' if !in_string => depth += 1,
+                '"' => in_string = !in_string,
+                '\\' if in_string => {
+                    buffer.push(c);
+                    if let Some(c) = self.chars.next()? { buffer.push(c) }
+                    continue
+                },
+                _ => (),
+            }
+            buffer.push(c);
+        }
+    }
+
+    /*
+     * Skips the whitespace following a key, then its ':' if any.
+     */
+    fn lex_colon(&mut self) -> Result<bool, ParseError> {
+        self.chars.end_token();
+        while let Some(c) = self.chars.peek()? {
+            if c == ':' {
+                self.chars.next()?;
+                return Ok(true)
+            }
+            if !c.is_whitespace() { break }
+            self.chars.next()?;
+        }
+        Ok(false)
+    }
+
+    /*
+     * A quoted string directly followed by ':' is an object key.
+     */
+    fn lex_name_or_string(&mut self, s:String) -> Result<ParseToken, ParseError> {
+        if self.lex_colon()? { Ok(ParseToken::Name(s)) }
+        else { Ok(ParseToken::Atom(ParseAtom::String(s))) }
+    }
+
+    /*
+     * Keys may also contain '-' and '.' (`max-size`, `log.level`), read
+     * along with the identifier only when a ':' follows
+     */
+    fn lex_key_rest(&mut self, mut id:String) -> Result<String, ParseError> {
+        let mut n = 0;
+        while self.chars.peek_nth(n)?.is_some_and(is_key_char) { n += 1 }
+        if n == 0 { return Ok(id) }
+        let mut colon = n;
+        while self.chars.peek_nth(colon)?.is_some_and(char::is_whitespace) { colon += 1 }
+        if self.chars.peek_nth(colon)? != Some(':') { return Ok(id) }
+        for _ in 0..n { id.extend(self.chars.next()?) }
+        Ok(id)
+    }
+
+    /*
+     * An identifier directly followed by ':' is an object key.
+     */
+    fn lex_name_or_id(&mut self) -> Result<ParseToken, ParseError> {
+        let start = self.current_position();
+        let id = self.lex_id()?;
+
+        if id == "r" && matches!(self.chars.peek()?, Some('"') | Some('#')) {
+            let raw = self.lex_raw(start)?;
+            return self.lex_name_or_string(raw)
+        }
+        let id = self.lex_key_rest(id)?;
+        if self.lex_colon()? { return Ok(ParseToken::Name(id)) }
+
+        // also manages "ID-like keywords" such as true, false, null, inf or nan
+        Ok(match id.as_ref() {
+            "true" => ParseToken::Atom(ParseAtom::Bool(true)),
+            "false" => ParseToken::Atom(ParseAtom::Bool(false)),
+            "null" => ParseToken::Atom(ParseAtom::Null),
+            _ => match special_float(&id) {
+                Some(v) => ParseToken::Atom(ParseAtom::Number(Num::Float(v))),
+                None => ParseToken::Id(id),
+            },
+        })
+    }
+
+    /*
+     * lex() reads the next token of the stream, None at the end
+     */
+    fn lex(&mut self) -> Result<Option<(ParseToken, Position)>, ParseError> {
+        let quoteless = ::std::mem::replace(&mut self.quoteless, false);
+        self.after_comma = false;
+
+        while let Some(c) = self.chars.peek()? {
+            let start = self.current_position();
+            self.chars.start_token();
+
+            // Quoteless case
+            // HJSON values which are not delimited, HJSON having no anchors,
+            // aliases nor variables
+            if quoteless && !c.is_whitespace() && !is_kwd(c) && !is_comment_begin(c)
+                && !matches!(c, ',' | '"' | '\'') {
+                return Ok(Some((self.lex_quoteless()?, start)))
+            }
+
+            // ID case
+            else if is_id_begin(c) {
+                return Ok(Some((self.lex_name_or_id()?, start)))
+            }
+
+            // Double case
+            // manages f64 numbers, and document separators
+            else if is_double_begin(c) {
+                return Ok(Some((self.lex_number()?, start)))
+            }
+
+            // Keyword case
+            // manages every 1 character keys (mostly delimiters such as '{' '}')
+            else if is_kwd(c) {
+                self.chars.next()?;
+                return Ok(Some((ParseToken::Key(c), start)))
+            }
+
+            // Comment case
+            // comments are forwarded to the reader, which may hand them
+            // to the user for dumping them into a file, if wanted
+            else if is_comment_begin(c) {
+                if let Some(comment) = self.lex_comment()? {
+                    return Ok(Some((ParseToken::Comment(comment), start)))
+                }
+            }
+
+            // Reference case
+            // '&' names the value which follows, '*' refers to it
+            else if c == '&' || c == '*' {
+                self.chars.next()?;
+                match self.chars.peek()? {
+                    Some(n) if is_id_begin(n) => {
+                        let name = self.lex_id()?;
+                        let tok = if c == '&' { ParseToken::Anchor(name) } else { ParseToken::Alias(name) };
+                        return Ok(Some((tok, start)))
+                    },
+                    _ => self.stray(c, start)?,
+                }
+            }
+
+            // Variable and expression case
+            // "${name}" is left for an Interpolator to substitute, "$(a + b)"
+            // is evaluated once the document is read
+            else if c == '$' {
+                self.chars.next()?;
+                if self.chars.peek()? == Some('{') {
+                    self.chars.next()?;
+                    let mut name = String::new();
+                    loop {
+                        match self.chars.next()? {
+                            None => return Err(ParseError::new(ErrorKind::UnexpectedEof, start)),
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                        }
+                    }
+                    return Ok(Some((ParseToken::Variable(name), start)))
+                }
+                if self.chars.peek()? == Some('(') {
+                    self.chars.next()?;
+                    return Ok(Some((ParseToken::Expression(self.lex_expression(start)?), start)))
+                }
+                self.stray(c, start)?
+            }
+
+            // Directive case
+            // "@include" is followed by the path of a file to include
+            else if c == '@' {
+                self.chars.next()?;
+                if self.chars.peek()?.is_some_and(is_id_begin) {
+                    let tok = self.lex_name_or_id()?;
+                    if tok == ParseToken::Id("include".to_string()) { return Ok(Some((ParseToken::Include, start))) }
+                    self.stray(c, start)?;
+                    return Ok(Some((tok, start)))
+                }
+                self.stray(c, start)?
+            }
+
+            // String case
+            else if c == '"' {
+                let string = self.lex_string(false)?;
+                return Ok(Some((self.lex_name_or_string(string)?, start)))
+            }
+
+            // Folded string case
+            // '>' before a double quoted string joins its lines
+            else if c == '>' {
+                self.chars.next()?;
+                if self.chars.peek()? == Some('"') {
+                    let string = self.lex_string(true)?;
+                    return Ok(Some((self.lex_name_or_string(string)?, start)))
+                }
+                self.stray(c, start)?
+            }
+
+            // Comma case
+            // commas may separate values, as in JSON
+            else if c == ',' {
+                self.chars.next()?;
+                self.after_comma = true;
+                if !self.commas {
+                    return Err(ParseError::new(ErrorKind::UnexpectedToken("`,`".to_string()), start))
+                }
+            }
+
+            // Single quoted and multi-line string case
+            // '' is empty, while ''' starts a multi-line string
+            else if c == '\'' {
+                self.chars.next()?;
+                let string = if self.chars.peek()? != Some('\'') { join(self.lex_quoted('\'', start)?) }
+                else {
+                    self.chars.next()?;
+                    if self.chars.peek()? != Some('\'') { String::new() }
+                    else {
+                        self.chars.next()?;
+                        self.lex_multiline(start)?
+                    }
+                };
+                return Ok(Some((self.lex_name_or_string(string)?, start)))
+            }
+
+            else {
+                self.chars.next()?;
+                if !c.is_whitespace() { self.stray(c, start)? }
+            }
+        }
+
+        Ok(None)
+    }
+}
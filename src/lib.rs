@@ -5,22 +5,85 @@
 
 #[macro_use]
 extern crate serde_derive;
+#[macro_use]
 extern crate serde;
 
 mod shaun_type;
+mod number;
+mod datetime;
+mod error;
+mod diagnostic;
+mod options;
+mod lexer;
+mod reader;
+mod path;
+mod document;
+mod expr;
 mod parser;
+mod loader;
+mod interpolate;
+mod de;
+mod writer;
+mod records;
 mod visitor;
 
 // Shaun type exports
 pub use shaun_type::Shaun;
 pub use shaun_type::ShaunError;
-pub use shaun_type::ShaunError::*;
+pub use shaun_type::ShaunErrorKind;
+pub use shaun_type::ShaunErrorKind::*;
 pub use shaun_type::Shaun::*;
+pub use number::Num;
+#[cfg(feature = "decimal")]
+pub use number::Decimal;
+pub use datetime::DateTime;
+pub use datetime::Date;
+pub use datetime::Time;
+
+// streaming exports
+pub use error::ParseError;
+pub use error::ErrorKind;
+pub use error::Position;
+pub use diagnostic::Diagnostic;
+pub use options::ParseOptions;
+pub use options::DuplicateKeys;
+pub use options::Limit;
+pub use reader::Reader;
+pub use reader::Event;
+pub use writer::Writer;
+pub use records::records;
+pub use records::Records;
+pub use records::RecordWriter;
 
 // parsing functions export
 pub use parser::parse_str;
 pub use parser::parse_string;
 pub use parser::parse_file;
+pub use parser::parse_reader;
+pub use parser::parse_hjson;
+pub use parser::parse_with_options;
+pub use parser::parse_recovering;
+pub use parser::parse_all_documents;
+pub use parser::documents;
+pub use parser::Documents;
+pub use parser::parse_unresolved;
+pub use document::Document;
+pub use expr::evaluate;
+
+// include loader exports
+pub use loader::Loader;
+pub use loader::SourceMap;
+pub use loader::Span;
+
+// variable interpolation exports
+pub use interpolate::Resolver;
+pub use interpolate::Env;
+pub use interpolate::Secrets;
+pub use interpolate::Interpolator;
+
+// serde functions export
+pub use de::from_reader;
+pub use de::from_shaun;
 
 pub use visitor::Visitor;
 pub use visitor::PrettyPrinter;
@@ -0,0 +1,174 @@
+use shaun_type::Shaun;
+use error::{ErrorKind, ParseError, Position};
+use parser::{build_with, Hooks};
+use reader::Reader;
+use path::{PathDisplay, Segment};
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// Where a value was read: the file, and the position in that file.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Span {
+    pub file : PathBuf,
+    pub position : Position,
+}
+
+/// Spans of the values of a loaded document, indexed by their path
+/// (keys separated by dots, list indices in brackets, such as
+/// `"servers[0].port"`, the root value being at `""`).
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct SourceMap {
+    spans : HashMap<String, Span>,
+}
+
+impl SourceMap {
+    pub fn get(&self, path:&str) -> Option<&Span> {
+        self.spans.get(path)
+    }
+
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+}
+
+/// Loads SHAUN files, resolving the `@include "other.sn"` directives
+/// they contain.
+///
+/// An include placed among the entries of an object adds the entries of
+/// the included file to that object, while an include placed as a value
+/// (`net: @include "net.sn"`) stands for the content of the file. Included
+/// paths are looked up relative to the including file first, then in the
+/// search paths, in order.
+///
+/// # Examples
+/// ```
+/// use shaun::Loader;
+/// use std::path::Path;
+///
+/// let loader = Loader::new().search_path("resources");
+/// let parsed = loader.load(Path::new("resources/my_file.sn")).unwrap();
+/// println!("Parsed value is {:?}", parsed);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Loader {
+    search_paths : Vec<PathBuf>,
+    max_depth : usize,
+}
+
+impl Default for Loader {
+    fn default() -> Loader {
+        Loader::new()
+    }
+}
+
+impl Loader {
+    pub fn new() -> Loader {
+        Loader { search_paths: Vec::new(), max_depth: 32 }
+    }
+
+    /// Adds a directory where included files are looked for.
+    pub fn search_path<P : Into<PathBuf>>(mut self, path:P) -> Loader {
+        self.search_paths.push(path.into());
+        self
+    }
+
+    /// Sets how many files can be included into each other, 32 by default.
+    pub fn max_depth(mut self, depth:usize) -> Loader {
+        self.max_depth = depth;
+        self
+    }
+
+    pub fn load(&self, path:&Path) -> Result<Shaun, ParseError> {
+        self.load_with_sources(path).map(|(sn, _)| sn)
+    }
+
+    /// Loads a file, along with the file and position each value comes from.
+    pub fn load_with_sources(&self, path:&Path) -> Result<(Shaun, SourceMap), ParseError> {
+        let mut sources = SourceMap::default();
+        let sn = self.load_file(path, &mut Vec::new(), "", false, &mut sources)?;
+        Ok((sn, sources))
+    }
+
+    fn load_file(&self, path:&Path, active:&mut Vec<PathBuf>, prefix:&str, merged:bool, sources:&mut SourceMap)
+        -> Result<Shaun, ParseError> {
+        let file = File::open(path)
+            .map_err(|e| ParseError::new(ErrorKind::Io(e), Position::start()).in_file(path))?;
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+
+        active.push(canonical);
+        let ret = {
+            let mut hooks = LoaderHooks { loader: self, file: path, active, prefix, merged, sources };
+            build_with(&mut Reader::new(file), &mut hooks)
+        };
+        active.pop();
+
+        match ret.and_then(|doc| doc.map(|d| d.resolve()).transpose()) {
+            Ok(Some(sn)) => Ok(sn),
+            Ok(None) => Ok(Shaun::Object(HashMap::new())),
+            Err(e) => Err(e.in_file(path)),
+        }
+    }
+
+    fn resolve(&self, name:&str, including:&Path) -> Option<PathBuf> {
+        let name = Path::new(name);
+        if name.is_absolute() {
+            return Some(name.to_path_buf()).filter(|p| p.is_file())
+        }
+
+        let dir = including.parent().unwrap_or_else(|| Path::new(""));
+        ::std::iter::once(dir)
+            .chain(self.search_paths.iter().map(|p| p.as_path()))
+            .map(|d| d.join(name))
+            .find(|p| p.is_file())
+    }
+}
+
+struct LoaderHooks<'a> {
+    loader : &'a Loader,
+    file : &'a Path,
+    active : &'a mut Vec<PathBuf>,
+    prefix : &'a str,
+    merged : bool,
+    sources : &'a mut SourceMap,
+}
+
+fn join(prefix:&str, path:&str) -> String {
+    if prefix.is_empty() { path.to_string() }
+    else if path.is_empty() || path.starts_with('[') { format!("{}{}", prefix, path) }
+    else { format!("{}.{}", prefix, path) }
+}
+
+impl<'a> Hooks for LoaderHooks<'a> {
+    fn tracks_values(&self) -> bool { true }
+
+    fn value(&mut self, path:&[Segment], at:Position) {
+        // the root of a merged file is the object it is merged into
+        if self.merged && path.is_empty() { return }
+        let span = Span { file: self.file.to_path_buf(), position: at };
+        self.sources.spans.insert(join(self.prefix, &PathDisplay(path).to_string()), span);
+    }
+
+    fn include(&mut self, name:&str, path:&[Segment], merged:bool, at:Position) -> Result<Shaun, ParseError> {
+        let error = |kind| ParseError::new(kind, at);
+
+        let included = self.loader.resolve(name, self.file)
+            .ok_or_else(|| error(ErrorKind::IncludeNotFound(name.to_string())))?;
+        let canonical = included.canonicalize().unwrap_or_else(|_| included.clone());
+
+        if self.active.contains(&canonical) {
+            return Err(error(ErrorKind::IncludeCycle(included)))
+        }
+        if self.active.len() > self.loader.max_depth {
+            return Err(error(ErrorKind::IncludeDepth(self.loader.max_depth)))
+        }
+
+        let prefix = join(self.prefix, &PathDisplay(path).to_string());
+        self.loader.load_file(&included, self.active, &prefix, merged, self.sources)
+    }
+}
@@ -0,0 +1,270 @@
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::{Serialize, Serializer};
+
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+
+/// A number as written in SHAUN data.
+///
+/// Integers are kept exactly, as long as they fit in 64 bits, while
+/// other numbers are floats, or decimals with the `decimal` feature.
+/// Numbers compare by value, whatever their representation, so that
+/// `Num::UInt(2) == Num::Float(2.0)`.
+///
+/// `Num` is `Clone` but not `Copy`, and matching on it needs a wildcard
+/// arm, whether the `decimal` feature is enabled or not, so that enabling
+/// the feature anywhere in a build breaks no other crate.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum Num {
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    #[cfg(feature = "decimal")]
+    Decimal(Decimal),
+}
+
+impl Num {
+    pub fn as_f64(&self) -> f64 {
+        match *self {
+            Num::Int(i) => i as f64,
+            Num::UInt(u) => u as f64,
+            Num::Float(f) => f,
+            #[cfg(feature = "decimal")]
+            Num::Decimal(ref d) => d.as_f64(),
+        }
+    }
+
+    /// The number as an i64, if it is an integer within its range.
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_i128().and_then(|i| i64::try_from(i).ok())
+    }
+
+    /// The number as a u64, if it is a non-negative integer within its range.
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_i128().and_then(|i| u64::try_from(i).ok())
+    }
+
+    /// Whether the number is an integer held exactly.
+    pub fn is_integer(&self) -> bool {
+        self.as_i128().is_some()
+    }
+
+    /// The number as an i128, if it is an integer held exactly.
+    pub fn as_i128(&self) -> Option<i128> {
+        match *self {
+            Num::Int(i) => Some(i as i128),
+            Num::UInt(u) => Some(u as i128),
+            Num::Float(_) => None,
+            #[cfg(feature = "decimal")]
+            Num::Decimal(ref d) => d.as_i128(),
+        }
+    }
+
+    #[cfg(feature = "decimal")]
+    fn as_decimal(&self) -> Option<Decimal> {
+        match *self {
+            Num::Int(i) => Decimal::parse(&i.to_string()),
+            Num::UInt(u) => Decimal::parse(&u.to_string()),
+            Num::Float(_) => None,
+            Num::Decimal(ref d) => Some(d.clone()),
+        }
+    }
+}
+
+/// A decimal number of any precision, which keeps the text it was
+/// written with (available with the `decimal` feature).
+///
+/// # Examples
+/// ```
+/// use shaun::Decimal;
+///
+/// let d = Decimal::parse("0.10").unwrap();
+/// assert_eq!(d.to_string(), "0.10");
+/// assert_eq!(d, Decimal::parse("1e-1").unwrap());
+/// assert_eq!(d.as_f64(), 0.1);
+/// ```
+#[cfg(feature = "decimal")]
+#[derive(Debug, Clone)]
+pub struct Decimal {
+    text : String,
+    negative : bool,
+    // significant digits, without leading nor trailing zeros, along with
+    // the power of ten of the last one
+    digits : String,
+    exponent : i64,
+}
+
+#[cfg(feature = "decimal")]
+impl Decimal {
+    /// Reads a decimal number, such as `-12.50`, `1_000.5` or `6.02e23`.
+    pub fn parse(text:&str) -> Option<Decimal> {
+        let t = text.replace('_', "");
+        let (negative, t) = match t.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, t.strip_prefix('+').unwrap_or(&t)),
+        };
+        let (mantissa, exponent) = match t.find(['e', 'E']) {
+            Some(i) => (&t[..i], t[i+1..].parse::<i64>().ok()?),
+            None => (t, 0),
+        };
+        let (int, frac) = match mantissa.find('.') {
+            Some(i) => (&mantissa[..i], &mantissa[i+1..]),
+            None => (mantissa, ""),
+        };
+        if (int.is_empty() && frac.is_empty()) || !int.chars().chain(frac.chars()).all(|c| c.is_ascii_digit()) {
+            return None
+        }
+
+        let all = format!("{}{}", int, frac);
+        let significant = all.trim_start_matches('0');
+        let digits = significant.trim_end_matches('0');
+        // exponents near the bounds of i64 are not numbers
+        let exponent = exponent.checked_sub(frac.len() as i64)?.checked_add((significant.len() - digits.len()) as i64)?;
+        Some(Decimal {
+            text: text.to_string(),
+            negative: negative && !digits.is_empty(),
+            digits: digits.to_string(),
+            exponent: if digits.is_empty() { 0 } else { exponent },
+        })
+    }
+
+    /// The closest f64 to the number.
+    pub fn as_f64(&self) -> f64 {
+        self.text.replace('_', "").parse().unwrap_or(f64::NAN)
+    }
+
+    /// The number as an i128, if it is an integer within its range.
+    pub fn as_i128(&self) -> Option<i128> {
+        if self.exponent < 0 || self.digits.len() as i128 + self.exponent as i128 > 39 { return None }
+        let zeros = "0".repeat(self.exponent as usize);
+        let magnitude : i128 = format!("0{}{}", self.digits, zeros).parse().ok()?;
+        Some(if self.negative { -magnitude } else { magnitude })
+    }
+
+    fn compare(&self, other:&Decimal) -> Ordering {
+        let sign = |d:&Decimal| if d.digits.is_empty() { 0 } else if d.negative { -1 } else { 1 };
+        match sign(self).cmp(&sign(other)) {
+            Ordering::Equal if sign(self) == 0 => return Ordering::Equal,
+            Ordering::Equal => (),
+            o => return o,
+        }
+
+        // the position of the first digit tells the magnitude
+        let magnitude = |d:&Decimal| d.digits.len() as i128 + d.exponent as i128;
+        let ret = magnitude(self).cmp(&magnitude(other)).then_with(|| {
+            let n = self.digits.len().max(other.digits.len());
+            format!("{:0<n$}", self.digits, n = n).cmp(&format!("{:0<n$}", other.digits, n = n))
+        });
+        if self.negative { ret.reverse() } else { ret }
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl PartialEq for Decimal {
+    fn eq(&self, other:&Decimal) -> bool {
+        self.compare(other) == Ordering::Equal
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl fmt::Display for Decimal {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl PartialEq for Num {
+    fn eq(&self, other:&Num) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Num {
+    fn partial_cmp(&self, other:&Num) -> Option<Ordering> {
+        #[cfg(feature = "decimal")]
+        {
+            if let (Some(a), Some(b)) = (self.as_decimal(), other.as_decimal()) {
+                return Some(a.compare(&b))
+            }
+        }
+
+        match (self.as_i128(), other.as_i128()) {
+            (Some(a), Some(b)) => Some(a.cmp(&b)),
+            _ => self.as_f64().partial_cmp(&other.as_f64()),
+        }
+    }
+}
+
+impl fmt::Display for Num {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Num::Int(i) => write!(f, "{}", i),
+            Num::UInt(u) => write!(f, "{}", u),
+            Num::Float(x) if x.is_nan() => write!(f, "nan"),
+            Num::Float(x) if x.is_infinite() => write!(f, "{}inf", if x < 0.0 { "-" } else { "" }),
+            Num::Float(x) => write!(f, "{}", x),
+            #[cfg(feature = "decimal")]
+            Num::Decimal(ref d) => write!(f, "{}", d),
+        }
+    }
+}
+
+impl From<f64> for Num {
+    fn from(f:f64) -> Num {
+        Num::Float(f)
+    }
+}
+
+impl From<i64> for Num {
+    fn from(i:i64) -> Num {
+        Num::Int(i)
+    }
+}
+
+impl From<u64> for Num {
+    fn from(u:u64) -> Num {
+        Num::UInt(u)
+    }
+}
+
+impl Serialize for Num {
+    fn serialize<S : Serializer>(&self, serializer:S) -> Result<S::Ok, S::Error> {
+        match *self {
+            Num::Int(i) => serializer.serialize_i64(i),
+            Num::UInt(u) => serializer.serialize_u64(u),
+            Num::Float(f) => serializer.serialize_f64(f),
+            #[cfg(feature = "decimal")]
+            Num::Decimal(ref d) => serializer.serialize_f64(d.as_f64()),
+        }
+    }
+}
+
+struct NumVisitor;
+
+impl<'de> Visitor<'de> for NumVisitor {
+    type Value = Num;
+
+    fn expecting(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a number")
+    }
+
+    fn visit_i64<E : de::Error>(self, i:i64) -> Result<Num, E> {
+        Ok(if i < 0 { Num::Int(i) } else { Num::UInt(i as u64) })
+    }
+
+    fn visit_u64<E : de::Error>(self, u:u64) -> Result<Num, E> {
+        Ok(Num::UInt(u))
+    }
+
+    fn visit_f64<E : de::Error>(self, f:f64) -> Result<Num, E> {
+        Ok(Num::Float(f))
+    }
+}
+
+impl<'de> Deserialize<'de> for Num {
+    fn deserialize<D : Deserializer<'de>>(deserializer:D) -> Result<Num, D::Error> {
+        deserializer.deserialize_any(NumVisitor)
+    }
+}
@@ -0,0 +1,105 @@
+use std::fmt;
+
+/// What to do with a key found twice in the same object.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum DuplicateKeys {
+    /// Fails with `ErrorKind::DuplicateKey`.
+    Error,
+    /// Keeps the first value.
+    FirstWins,
+    /// Keeps the last value.
+    #[default]
+    LastWins,
+    /// Gathers every value in a list.
+    Collect,
+}
+
+/// A limit set by `ParseOptions`, as reported by
+/// `ErrorKind::LimitExceeded`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Limit {
+    Depth,
+    InputSize,
+    StringLength,
+    Keys,
+    ListLength,
+    Values,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Limit::Depth => write!(f, "nesting depth"),
+            Limit::InputSize => write!(f, "input size"),
+            Limit::StringLength => write!(f, "string length"),
+            Limit::Keys => write!(f, "number of keys"),
+            Limit::ListLength => write!(f, "list length"),
+            Limit::Values => write!(f, "number of values"),
+        }
+    }
+}
+
+/// How SHAUN data is read, by a `Reader` or `parse_with_options`.
+///
+/// The default options are the ones of `parse_reader`: unknown characters
+/// and escapes are skipped, commas may separate values, and the last of
+/// duplicate keys wins. Nothing is limited either: untrusted input should
+/// be read with limits, which fail with `ErrorKind::LimitExceeded` as
+/// soon as they are exceeded, before going on reading.
+///
+/// # Examples
+/// ```
+/// use shaun::{parse_with_options, DuplicateKeys, ParseOptions};
+///
+/// let options = ParseOptions { strict: true, duplicate_keys: DuplicateKeys::Error, ..ParseOptions::default() };
+/// assert!(parse_with_options("port: 80;".as_bytes(), &options).is_err());
+/// assert!(parse_with_options("port: 80 port: 81".as_bytes(), &options).is_err());
+/// assert!(parse_with_options("port: 80, host: \"a\"".as_bytes(), &options).is_ok());
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParseOptions {
+    /// Rejects characters which are not part of SHAUN, unknown escapes in
+    /// strings, and bare words likely to be typos of `true`, `false` or
+    /// `null`.
+    pub strict : bool,
+    pub duplicate_keys : DuplicateKeys,
+    /// Accepts commas between values and entries, as in JSON.
+    pub allow_commas : bool,
+    /// Reads HJSON, as `parse_hjson`.
+    pub hjson : bool,
+    /// Maximum number of nested objects and lists, the root object
+    /// counting as one, and of nested parentheses, lists, calls and signs
+    /// in an expression. Expressions never nest deeper than 64 levels.
+    pub max_depth : Option<usize>,
+    /// Maximum number of bytes read.
+    pub max_input_size : Option<u64>,
+    /// Maximum number of bytes of a string, key or comment, as written.
+    pub max_string_length : Option<u64>,
+    /// Maximum number of keys of an object.
+    pub max_keys : Option<usize>,
+    /// Maximum number of values of a list.
+    pub max_list_length : Option<usize>,
+    /// Maximum number of values of a document once its aliases are
+    /// resolved, every object, list and scalar counting as one, so that a
+    /// few nested aliases cannot copy values without bound. Defaults to
+    /// `max_input_size`, a document without aliases never holding more
+    /// values than bytes.
+    pub max_values : Option<u64>,
+}
+
+impl Default for ParseOptions {
+    fn default() -> ParseOptions {
+        ParseOptions {
+            strict: false,
+            duplicate_keys: DuplicateKeys::LastWins,
+            allow_commas: true,
+            hjson: false,
+            max_depth: None,
+            max_input_size: None,
+            max_string_length: None,
+            max_keys: None,
+            max_list_length: None,
+            max_values: None,
+        }
+    }
+}
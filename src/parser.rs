@@ -1,385 +1,273 @@
 use shaun_type::Shaun;
+use error::{ErrorKind, ParseError, Position};
+use reader::{Event, Reader};
+use options::{DuplicateKeys, ParseOptions};
+use document::{Document, References};
+use path::Segment;
 
 use std::fs::File;
-use std::io::prelude::*;
+use std::io::Read;
 use std::path::Path;
 
-use std::collections::HashMap;
-use std::iter::Peekable;
-use std::vec::Vec;
-
-static mut COL : u32 = 0;
-static mut ROW : u32 = 0;
-
-fn advance<I>(stream : &mut Peekable<I>) where I : Iterator<Item=char> {
-    unsafe {
-        if let Some('\n') = stream.next() {
-            COL = 0;
-            ROW = ROW + 1;
-        } else {
-            COL = COL + 1;
-        }
-    }
-}
-
-#[derive(Debug, PartialEq)]
-enum ParseToken {
-    Key(String),
-    Id(String),
-    Atom(ParseAtom),
-    Comment(String),
-}
-
-#[derive(Debug, PartialEq)]
-enum ParseAtom {
-    String(String),
-    Bool(bool),
-    Double(f64),
-    Null,
-}
+use std::collections::{HashMap, HashSet};
+use std::mem;
 
 /*
- * SlideWindow represents a fixed size sliding [char]:
- *
- * let window : SlideWindow = SlideWindow::new(3)
- * window.push('a')    //  "a"
- * window.push('b')    //  "ab"
- * window.push('c')    //  "bc"
- * window.push('d')    //  "cd"
+ * Hooks let the caller of build() resolve includes and follow where
+ * each value comes from
  */
-#[derive(Debug)]
-struct SlideWindow {
-    arr : Vec<char>,
-    id  : usize,
-}
+pub trait Hooks {
+    // value() is only called if this returns true
+    fn tracks_values(&self) -> bool { false }
 
-impl SlideWindow {
-    pub fn new(n:usize) -> SlideWindow { let mut v = Vec::new(); v.resize(n, '\0'); SlideWindow { arr: v, id : 0 } }
+    fn value(&mut self, _path:&[Segment], _at:Position) {}
 
-    pub fn push(&mut self, e:char) -> () {
-        let len = self.arr.len();
-        if self.id < len { self.arr[self.id] = e; self.id = self.id+1; return; }
-        for i in 1..len {
-            self.arr[i-1] = self.arr[i].clone();
-        }
-        self.arr[len-1] = e
+    fn string(&mut self, s:String, _at:Position) -> Result<Shaun, ParseError> {
+        Ok(Shaun::String(s))
     }
 
-    pub fn first(&self) -> char {
-        self.arr[0]
+    // without an Interpolator, variables are kept as they were written
+    fn variable(&mut self, name:&str, _at:Position) -> Result<Shaun, ParseError> {
+        Ok(Shaun::String(format!("${{{}}}", name)))
     }
-}
 
-impl PartialEq<String> for SlideWindow {
-    fn eq(&self, other:&String) -> bool {
-        let vec : Vec<char> = other.chars().collect();
-        self.arr == vec
+    // merged tells whether the entries of the included object are merged
+    // into the object at path, rather than becoming the value at path
+    fn include(&mut self, file:&str, _path:&[Segment], _merged:bool, at:Position) -> Result<Shaun, ParseError> {
+        Err(ParseError::new(ErrorKind::UnresolvedInclude(file.to_string()), at))
     }
 
-    fn ne(&self, other:&String) -> bool {
-        let vec : Vec<char> = other.chars().collect();
-        self.arr != vec
+    // errors stop building, unless this returns Ok, in which case the
+    // reader recovers from them
+    fn error(&mut self, e:ParseError) -> Result<(), ParseError> {
+        Err(e)
     }
 }
 
-/*
- * Helper functions, mostly for testing characters
- */
-
-fn is_kwd(c:char) -> bool {
-    c == ':' ||
-    c == '{' ||
-    c == '}' ||
-    c == '[' ||
-    c == ']'
-}
-
-fn is_id_begin(c:char) -> bool {
-    c.is_alphabetic() || c == '_'
-}
+struct NoHooks;
 
-fn is_comment_begin(c:char) -> bool {
-    c == '/' || c == '#' || c == '('
-}
+impl Hooks for NoHooks {}
 
-fn is_double_begin(c:char) -> bool {
-    c.is_digit(10) || c == '.' || c == '-' || c == '+'
-}
-
-fn is_id_char(c:char) -> bool {
-    c.is_digit(10) || is_id_begin(c)
-}
-
-fn is_double_char(c:char) -> bool {
-    c.is_digit(10) || c == '.' || c == '-'
-        || c == '+' || c == 'e' || c == 'E'
-}
-
-/**
- * lex_<x> functions are functions which consume a stream
- * of char and return a value depending on the object we are
- * "lexing"
- *
- * every lex_<x> function must consume the stream to the caracter just after
- * the read symbol.
- *
- * "10 years" -> lex_number() -> "10 years"
- *  ^                               ^
- *  before                        after
+/*
+ * path() is the path of keys and indices leading to the next value
+ * of the stack
  */
-fn lex_number<I>(stream : &mut Peekable<I>) -> f64
-where I : Iterator<Item=char> {
-    let mut buffer = String::new();
-
-    while stream.peek().is_some() {
-        let c = *stream.peek().unwrap();
-        if is_double_char(c) { buffer.push(c) }
-        else { break }
-
-        advance(stream);
-    }
-
-    buffer.parse().unwrap()
-}
-
-fn lex_id<I>(stream : &mut Peekable<I>) -> String
-where I : Iterator<Item=char> {
-    let mut buffer = String::new();
-
-    while stream.peek().is_some() {
-        let c = *stream.peek().unwrap();
-        if is_id_char(c) { buffer.push(c) }
-        else { break }
-
-        advance(stream);
-    }
-
-    buffer
-}
-
-
-fn lex_comment<I>(stream : &mut Peekable<I>) -> String
-where I : Iterator<Item=char> {
-    let mut buffer = String::new();
-    let mut w : SlideWindow;
-    let end : String;
-
-    match stream.peek() {
-        None => return buffer,
-        Some(&'/') => {
-            advance(stream);
-            match stream.peek() {
-                None => return buffer,
-                Some(&'*') => { w = SlideWindow::new(2); end = String::from("*/") },
-                Some(&'/') => { w = SlideWindow::new(1); end = String::from("\n") },
-                _ => return buffer
-            }
-        }
-        Some(&'(') => { w = SlideWindow::new(1); end = String::from(")") },
-        Some(&'#') => { w = SlideWindow::new(1); end = String::from("\n") },
-        _ => return buffer
-    }
-
-    advance(stream);
-    while stream.peek().is_some() {
-        let c = *stream.peek().unwrap();
-        w.push(c);
-        if w == end { break }
-        buffer.push(w.first());
-        advance(stream);
-    }
-
-    advance(stream);
-    buffer
+fn path(stack : &[(Shaun, Option<String>)]) -> Vec<Segment> {
+    stack.iter().filter_map(|e| match *e {
+        (Shaun::Object(_), Some(ref k)) => Some(Segment::Key(k.clone())),
+        (Shaun::List(ref l), _) => Some(Segment::Index(l.len())),
+        _ => None,
+    }).collect()
 }
 
-fn lex_string<I>(stream : &mut Peekable<I>) -> String
-where I : Iterator<Item=char> {
-    let mut buffer = String::new();
-    advance(stream);
-
-    if let Some(&'\n') = stream.peek() {
-        advance(stream);
-    }
-
-    while stream.peek().is_some() {
-        let c = *stream.peek().unwrap();
-        if c == '"' { break }
-
-        if c == '\\' {
-            advance(stream);
-            let c2 = *stream.peek().unwrap();
-            buffer.push(match c2 {
-                'n' => '\n',
-                't' => '\t',
-                'r' => '\r',
-                _   => c2,
-            })
-        }
-        else {
-            buffer.push(c)
+/*
+ * unwind() closes the objects and lists of the stack, giving the root
+ */
+fn unwind(mut stack:Vec<(Shaun, Option<String>)>) -> Shaun {
+    let mut ret = Shaun::Object(HashMap::new());
+    while let Some((value, _)) = stack.pop() {
+        ret = value;
+        match stack.last_mut() {
+            Some(&mut (Shaun::Object(ref mut o), ref mut key)) => {
+                if let Some(k) = key.take() { o.insert(k, mem::replace(&mut ret, Shaun::Null)); }
+            },
+            Some(&mut (Shaun::List(ref mut l), _)) => l.push(mem::replace(&mut ret, Shaun::Null)),
+            _ => (),
         }
-
-        advance(stream);
     }
-    
-    advance(stream);
-    if let Some('\n') = buffer.chars().rev().next() { buffer.pop(); }
-    buffer
+    ret
 }
 
 /*
- * lex(str) transforms a str into a token vector
+ * build() assembles the events of a reader into a Shaun value, using
+ * its own stack of unfinished objects and lists rather than recursion,
+ * then resolves its references. It returns None if the reader has no
+ * more document.
  */
-fn lex<I>(it:&mut Peekable<I>) -> Vec<ParseToken>
-where I : Iterator<Item=char> {
-    unsafe {
-        COL = 0;
-        ROW = 0;
+fn build<R : Read>(reader : &mut Reader<R>) -> Result<Option<Shaun>, ParseError> {
+    match build_with(reader, &mut NoHooks)? {
+        Some(doc) => doc.resolve().map(Some),
+        None => Ok(None),
     }
-    let mut ret = Vec::new();
+}
+
+pub fn build_with<R : Read, H : Hooks>(reader : &mut Reader<R>, hooks : &mut H) -> Result<Option<Document>, ParseError> {
+    let mut stack : Vec<(Shaun, Option<String>)> = Vec::new();
+    let mut refs = References::default();
+    let duplicates = reader.options().duplicate_keys;
+    let max_values = reader.options().max_values.or(reader.options().max_input_size);
+    // keys of each open object whose list gathers the values of duplicates
+    let mut collected : Vec<HashSet<String>> = Vec::new();
 
     loop {
-        match it.peek() { None => break, Some(&c) => {
-                // ID case
-                // also manages "ID-like keywords" such as true, false, or null
-                if is_id_begin(c) {
-                    let id = lex_id(it);
-                    if id == "true" { ret.push(ParseToken::Atom(ParseAtom::Bool(true))) }
-                    else if id == "false" { ret.push(ParseToken::Atom(ParseAtom::Bool(false))) }
-                    else if id == "null" { ret.push(ParseToken::Atom(ParseAtom::Null)) }
-                    else { ret.push(ParseToken::Id(id)) }
-                }
+        let event = match reader.next_event() {
+            Ok(None) if stack.is_empty() => return Ok(None),
+            Ok(None) => return Err(ParseError::new(ErrorKind::UnexpectedEof, reader.current_position())),
+            Ok(Some(e)) => e,
+            Err(e) => {
+                let recovered = reader.recover(&e);
+                hooks.error(e)?;
+                if recovered { continue }
+                // errors found while skipping come before the end
+                while let Err(e) = reader.next_event() { hooks.error(e)? }
+                return Ok(Some(Document::new(unwind(stack), refs).max_values(max_values)))
+            },
+        };
+
+        match event {
+            Event::Null | Event::Bool(_) | Event::Number(_, _) | Event::DateTime(_) | Event::String(_)
+                | Event::Variable(_) | Event::Expression(_) | Event::StartObject | Event::StartList if hooks.tracks_values() => {
+                hooks.value(&path(&stack), reader.event_position())
+            },
+            _ => (),
+        }
 
-                // Double case
-                // manages f64 numbers
-                else if is_double_begin(c) {
-                    let dbl = lex_number(it);
-                    ret.push(ParseToken::Atom(ParseAtom::Double(dbl)))
+        let value = match event {
+            Event::Comment(_) | Event::Separator => continue,
+            Event::Anchor(name) => {
+                refs.anchor(name, path(&stack), reader.event_position())?;
+                continue
+            },
+            Event::Alias(name) => {
+                refs.alias(name, path(&stack), reader.event_position());
+                Shaun::Null
+            },
+            Event::Expression(e) => {
+                refs.expression(e, path(&stack), reader.event_position());
+                Shaun::Null
+            },
+            Event::Key(k) => {
+                if let Some(top) = stack.last_mut() {
+                    let duplicate = matches!(top.0, Shaun::Object(ref o) if o.contains_key(&k));
+                    if duplicate && duplicates == DuplicateKeys::Error {
+                        hooks.error(ParseError::new(ErrorKind::DuplicateKey(k.clone()), reader.event_position()))?;
+                    }
+                    top.1 = Some(k)
                 }
-
-                // Keyword case
-                // manages every 1 character keys (mostly delimiters such as '{' '}')
-                else if is_kwd(c) {
-                    ret.push(ParseToken::Key(c.to_string()));
-                    it.next();
+                continue
+            },
+            Event::StartObject => {
+                stack.push((Shaun::Object(HashMap::new()), None));
+                collected.push(HashSet::new());
+                continue
+            },
+            Event::StartList => {
+                stack.push((Shaun::List(Vec::new()), None));
+                collected.push(HashSet::new());
+                continue
+            },
+            Event::EndObject | Event::EndList => {
+                collected.pop();
+                match stack.pop() {
+                    Some((v, _)) => v,
+                    None => Shaun::Null,
                 }
-
-                // Comment case
-                // comments are embeded in the Shaun datastructure
-                // for dumping them into a file, if wanted
-                else if is_comment_begin(c) {
-                    let comment = lex_comment(it);
-                    ret.push(ParseToken::Comment(comment))
+            },
+            Event::Null => Shaun::Null,
+            Event::Bool(b) => Shaun::Bool(b),
+            Event::Number(n, unit) => Shaun::Number(n, unit),
+            Event::DateTime(d) => Shaun::DateTime(d),
+            Event::String(s) => hooks.string(s, reader.event_position())?,
+            Event::Variable(name) => hooks.variable(&name, reader.event_position())?,
+            Event::Include(file) => {
+                let merged = matches!(stack.last(), Some(&(Shaun::Object(_), None)));
+                let included = hooks.include(&file, &path(&stack), merged, reader.event_position())?;
+                match (stack.last_mut(), included) {
+                    (Some(&mut (Shaun::Object(ref mut o), None)), Shaun::Object(entries)) => {
+                        o.extend(entries);
+                        continue
+                    },
+                    (_, v) => v,
                 }
-
-                // String case
-                else if c == '"' {
-                    let string = lex_string(it);
-                    ret.push(ParseToken::Atom(ParseAtom::String(string)))
+            },
+        };
+
+        match stack.last_mut() {
+            None => return Ok(Some(Document::new(value, refs).max_values(max_values))),
+            Some(&mut (Shaun::Object(ref mut o), ref mut key)) => {
+                let key = key.take().unwrap_or_default();
+                match (duplicates, o.get_mut(&key), collected.last_mut()) {
+                    (DuplicateKeys::FirstWins, Some(_), _) => (),
+                    (DuplicateKeys::Collect, Some(first), Some(keys)) => match *first {
+                        Shaun::List(ref mut l) if keys.contains(&key) => l.push(value),
+                        _ => {
+                            let first_value = mem::replace(first, Shaun::Null);
+                            *first = Shaun::List(vec![first_value, value]);
+                            keys.insert(key);
+                        },
+                    },
+                    _ => { o.insert(key, value); },
                 }
-                else {
-                    it.next();
-                }
-            }
+            },
+            Some(&mut (ref mut l, _)) => {
+                if let Shaun::List(ref mut l) = *l { l.push(value) }
+            },
         }
     }
-
-    ret
 }
 
-fn atom_to_value(toks : &Vec<ParseToken>, i : &mut usize) -> Shaun {
-    let ret = match toks[*i] {
-        ParseToken::Atom(ParseAtom::Double(ref f)) => {
-            let id : String;
-            if toks.len() > *i+1 {
-                id = match toks[*i+1] {
-                    ParseToken::Id(ref unit) => { *i = *i + 1; unit.clone() },
-                    _ => String::new()
-                };
-            } else { id = String::new() }
-            Shaun::Number(*f, id)
-        },
-        ParseToken::Atom(ParseAtom::String(ref s)) => {
-            Shaun::String(s.clone())
-        },
-        ParseToken::Atom(ParseAtom::Bool(ref b)) => {
-            Shaun::Bool(*b)
-        },
-        _ => {
-            Shaun::Null
-        }
-    };
-
-    *i = *i + 1;
-    ret
-}
-
-fn parse_name(toks : &Vec<ParseToken>, i : &mut usize) -> String {
-    match toks[*i] {
-        ParseToken::Id(ref name) => { *i = *i + 1; name.clone() },
-        _ => String::new(),
-    }
-}
-
-fn parse_object(toks : &Vec<ParseToken>, i : &mut usize) -> Shaun {
-    *i = *i + 1;
-    let ret = parse_raw_object(toks, i);
-    *i = *i + 1;
-
-    ret
+/*
+ * parse_document() builds the value of the reader, then makes sure
+ * nothing but comments follows it
+ */
+fn parse_document<R : Read>(reader : &mut Reader<R>) -> Result<Shaun, ParseError> {
+    let ret = build(reader)?
+        .ok_or_else(|| ParseError::new(ErrorKind::UnexpectedEof, reader.current_position()))?;
+    while reader.next_event()?.is_some() {}
+    Ok(ret)
 }
 
-
-fn parse_raw_object(toks : &Vec<ParseToken>, i : &mut usize) -> Shaun {
-    let mut obj = HashMap::new();
-    while let ParseToken::Id(_) = toks[*i] {
-        let name = parse_name(toks, i);
-        *i = *i + 1;
-        let value = parse_value(toks, i);
-        obj.insert(name, value);
-        if *i >= toks.len() { break }
-    }
-
-    Shaun::Object(obj)
+/// Iterator over the documents of a reader, as returned by `documents`.
+pub struct Documents<R : Read> {
+    reader : Reader<R>,
 }
 
-fn parse_list(toks : &Vec<ParseToken>, i : &mut usize) -> Shaun {
-    let mut list = Vec::new();
+impl<R : Read> Iterator for Documents<R> {
+    type Item = Result<Shaun, ParseError>;
 
-    *i = *i + 1;
-    while toks[*i] != ParseToken::Key(']'.to_string()) {
-        list.push(parse_value(toks, i))
+    fn next(&mut self) -> Option<Result<Shaun, ParseError>> {
+        match build(&mut self.reader) {
+            Ok(Some(sn)) => Some(Ok(sn)),
+            Ok(None) => None,
+            Err(e) => Some(Err(e)),
+        }
     }
-
-    *i = *i + 1;
-
-    Shaun::List(list)
 }
 
-fn parse_value(toks : &Vec<ParseToken>, i : &mut usize) -> Shaun {
-    match toks[*i] {
-        ParseToken::Atom(ref _a) => atom_to_value(toks, i),
-        ParseToken::Key(ref k) => {
-            if *k == '{'.to_string() {
-                parse_object(toks, i)
-            } else if *k == '['.to_string() {
-                parse_list(toks, i)
-            } else { Shaun::Null }
-        }
-        _ => Shaun::Null
-    }
+/// Lazily parses the documents of a reader, separated by `---` lines.
+///
+/// # Examples
+/// ```
+/// use shaun::documents;
+///
+/// let input = "name: \"first\"\n---\nname: \"second\"\n";
+/// for doc in documents(input.as_bytes()) {
+///     println!("Parsed document is {:?}", doc.unwrap());
+/// }
+/// ```
+pub fn documents<R : Read>(reader:R) -> Documents<R> {
+    Documents { reader: Reader::new(reader).multi_document() }
 }
 
-fn parse_all(toks : &Vec<ParseToken>, i : &mut usize) -> Shaun {
-    match toks[*i] {
-        ParseToken::Key(ref k) => { if *k == '{'.to_string() { parse_object(toks, i) } else { Shaun::Null } },
-        _ => { parse_raw_object(toks, i) },
-    }
+/// Parses all the documents of a reader, separated by `---` lines.
+///
+/// # Examples
+/// ```
+/// use shaun::parse_all_documents;
+///
+/// let docs = parse_all_documents("---\na: 1\n---\n{ b: 2 }\n".as_bytes()).unwrap();
+/// assert_eq!(docs.len(), 2);
+/// ```
+pub fn parse_all_documents<R : Read>(reader:R) -> Result<Vec<Shaun>, ParseError> {
+    documents(reader).collect()
 }
 
 /// Parses a raw str to a Shaun value.
 ///
+/// # Panics
+/// Panics if the str is not valid SHAUN. `parse_reader(s.as_bytes())` or
+/// `parse_with_options` return the error instead.
+///
 /// # Examples
 /// ```
 /// use shaun::parse_str;
@@ -387,15 +275,18 @@ fn parse_all(toks : &Vec<ParseToken>, i : &mut usize) -> Shaun {
 /// let parsed = parse_str("{ am_i_parsed: true }");
 /// println!("Parsed value is {:?}", parsed);
 /// ```
-pub fn parse_str<'a>(s:&'a str) -> Shaun {
-    let mut it = s.chars().peekable();
-    let vec = lex(&mut it);
-    parse_all(&vec, &mut 0)
+pub fn parse_str(s:&str) -> Shaun {
+    parse_document(&mut Reader::new(s.as_bytes()))
+        .unwrap_or_else(|e| panic!("Shaun::parse_str(): {}", e))
 }
 
 
 /// Parses a String to a Shaun value.
 ///
+/// # Panics
+/// Panics if the String is not valid SHAUN. `parse_reader(s.as_bytes())`
+/// or `parse_with_options` return the error instead.
+///
 /// # Examples
 /// ```
 /// use shaun::parse_string;
@@ -404,13 +295,150 @@ pub fn parse_str<'a>(s:&'a str) -> Shaun {
 /// println!("Parsed value is {:?}", parsed);
 /// ```
 pub fn parse_string(s:String) -> Shaun {
-    let mut it = s.chars().peekable();
-    let vec = lex(&mut it);
-    parse_all(&vec, &mut 0)
+    parse_document(&mut Reader::new(s.as_bytes()))
+        .unwrap_or_else(|e| panic!("Shaun::parse_string(): {}", e))
+}
+
+/// Parses the content of any reader to a Shaun value.
+///
+/// Files, sockets, decompressors or the standard input can be read,
+/// as well as any `BufRead`. Invalid UTF-8 or I/O failures are reported
+/// as errors, along with malformed SHAUN.
+///
+/// # Examples
+/// ```
+/// use shaun::parse_reader;
+///
+/// let parsed = parse_reader("{ am_i_parsed: true }".as_bytes()).unwrap();
+/// println!("Parsed value is {:?}", parsed);
+///
+/// assert!(parse_reader(&b"name: \"\xff\""[..]).is_err());
+/// ```
+pub fn parse_reader<R : Read>(reader:R) -> Result<Shaun, ParseError> {
+    parse_document(&mut Reader::new(reader))
+}
+
+/// Parses the content of a reader as HJSON, whose unquoted values are
+/// strings running to the end of their line.
+///
+/// # Examples
+/// ```
+/// use shaun::{parse_hjson, Shaun};
+///
+/// let parsed = parse_hjson("{\n  name: my app\n  port: 8080\n}".as_bytes()).unwrap();
+/// assert_eq!(parsed.get("name").unwrap(), &Shaun::from("my app"));
+/// ```
+pub fn parse_hjson<R : Read>(reader:R) -> Result<Shaun, ParseError> {
+    parse_document(&mut Reader::new(reader).hjson())
+}
+
+/// Parses the content of a reader with the given options.
+///
+/// # Examples
+/// ```
+/// use shaun::{parse_with_options, DuplicateKeys, ParseOptions, Shaun};
+///
+/// let options = ParseOptions { duplicate_keys: DuplicateKeys::Collect, ..ParseOptions::default() };
+/// let parsed = parse_with_options("host: \"a\" host: \"b\"".as_bytes(), &options).unwrap();
+/// assert_eq!(parsed.get("host").unwrap(), &Shaun::from(vec!["a", "b"]));
+/// ```
+pub fn parse_with_options<R : Read>(reader:R, options:&ParseOptions) -> Result<Shaun, ParseError> {
+    parse_document(&mut Reader::with_options(reader, options.clone()))
+}
+
+struct Recovering(Vec<ParseError>);
+
+impl Hooks for Recovering {
+    fn error(&mut self, e:ParseError) -> Result<(), ParseError> {
+        self.0.push(e);
+        Ok(())
+    }
+}
+
+/// Parses the content of a reader, going on after errors, and returns
+/// what could be read along with every error found, for editors and
+/// linters. After an error, the parser skips to the next key or closing
+/// bracket, leaving out the value in error.
+///
+/// # Examples
+/// ```
+/// use shaun::{parse_recovering, ParseOptions, Shaun};
+///
+/// let (parsed, errors) = parse_recovering("a: 1 b: 0x c: { d: ] e: 2 } f: 3".as_bytes(), &ParseOptions::default());
+/// assert_eq!(errors.len(), 2);
+/// assert!(parsed.get("b").is_err());
+/// assert_eq!(parsed.get("c").unwrap().get("e").unwrap(), &Shaun::from(2.0));
+/// assert_eq!(parsed.get("f").unwrap(), &Shaun::from(3.0));
+/// ```
+pub fn parse_recovering<R : Read>(reader:R, options:&ParseOptions) -> (Shaun, Vec<ParseError>) {
+    let mut reader = Reader::with_options(reader, options.clone());
+    let mut hooks = Recovering(Vec::new());
+    let doc = build_with(&mut reader, &mut hooks);
+    let mut errors = hooks.0;
+
+    // what follows the document is read for its errors
+    loop {
+        match reader.next_event() {
+            Ok(Some(_)) => (),
+            Ok(None) => break,
+            Err(e) => {
+                let recovered = reader.recover(&e);
+                errors.push(e);
+                if !recovered { break }
+            },
+        }
+    }
+
+    match doc {
+        Ok(Some(doc)) => {
+            let (value, unresolved) = doc.resolve_all();
+            errors.extend(unresolved);
+            (value, errors)
+        },
+        Ok(None) => {
+            errors.push(ParseError::new(ErrorKind::UnexpectedEof, reader.current_position()));
+            (Shaun::Object(HashMap::new()), errors)
+        },
+        Err(e) => {
+            errors.push(e);
+            (Shaun::Object(HashMap::new()), errors)
+        },
+    }
+}
+
+/// Parses the content of a reader, keeping its anchors and aliases
+/// unresolved.
+///
+/// # Examples
+/// ```
+/// use shaun::{parse_unresolved, Writer};
+///
+/// let doc = parse_unresolved("a: &port 80 b: *port".as_bytes()).unwrap();
+/// let mut w = Writer::new(Vec::new());
+/// doc.write(&mut w).unwrap();
+/// ```
+pub fn parse_unresolved<R : Read>(reader:R) -> Result<Document, ParseError> {
+    parse_with(reader, &mut NoHooks)
+}
+
+/*
+ * parse_with() builds the single document of a reader with hooks
+ */
+pub fn parse_with<R : Read, H : Hooks>(reader:R, hooks:&mut H) -> Result<Document, ParseError> {
+    let mut reader = Reader::new(reader);
+    let ret = build_with(&mut reader, hooks)?
+        .ok_or_else(|| ParseError::new(ErrorKind::UnexpectedEof, reader.current_position()))?;
+    while reader.next_event()?.is_some() {}
+    Ok(ret)
 }
 
 /// Opens a file and parse its content.
 ///
+/// # Panics
+/// Panics if the file cannot be opened or is not valid SHAUN. Opening the
+/// file and reading it with `parse_reader` or `parse_with_options` gives
+/// the error instead, as does a `Loader`, which also resolves includes.
+///
 /// # Examples
 /// ```
 /// use shaun::parse_file;
@@ -420,17 +448,9 @@ pub fn parse_string(s:String) -> Shaun {
 /// println!("Parsed value is {:?}", parsed);
 /// ```
 pub fn parse_file(filepath:&Path) -> Shaun {
-    let mut s  = String::new();
-    let mut f = File::open(filepath)
+    let f = File::open(filepath)
         .expect("Shaun::parse_file(): Something went wrong while opening file.");
 
-    f.read_to_string(&mut s)
-        .expect("Shaun::parse_file(): Something went wrong while reading file.");
-    let mut it = s.chars().peekable();
-
-    let vec = lex(&mut it);
-    for e in &vec {
-        println!("{:?}", e)
-    }
-    parse_all(&vec, &mut 0)
+    parse_reader(f)
+        .unwrap_or_else(|e| panic!("Shaun::parse_file(): {}", e))
 }
@@ -0,0 +1,115 @@
+use shaun_type::Shaun;
+
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// A step of the path leading to a value: a key of an object, or an
+/// index of a list.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Displays a path as keys separated by dots, and indices in brackets,
+/// such as `servers[0].port`. Keys which cannot be written so are quoted
+/// in brackets, such as `["server.name"]`.
+pub struct PathDisplay<'a>(pub &'a [Segment]);
+
+impl<'a> fmt::Display for PathDisplay<'a> {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        for (i, s) in self.0.iter().enumerate() {
+            match *s {
+                Segment::Key(ref k) if k.is_empty() || k.contains(['.', '[', ']', '"']) => {
+                    write!(f, "[\"{}\"]", k.replace('\\', "\\\\").replace('"', "\\\""))?
+                },
+                Segment::Key(ref k) if i == 0 => write!(f, "{}", k)?,
+                Segment::Key(ref k) => write!(f, ".{}", k)?,
+                Segment::Index(n) => write!(f, "[{}]", n)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/*
+ * parse() splits a path such as `servers[0].port` or `["server.name"]`
+ * into its segments, an empty path being the root, and None if the path
+ * is malformed
+ */
+pub fn parse(text:&str) -> Option<Vec<Segment>> {
+    let mut ret = Vec::new();
+    let mut chars = text.chars().peekable();
+    loop {
+        match chars.peek() {
+            None => return Some(ret),
+            Some('[') => {
+                chars.next();
+                ret.push(bracketed(&mut chars)?)
+            },
+            Some('.') if !ret.is_empty() => {
+                chars.next();
+                ret.push(Segment::Key(bare(&mut chars)?))
+            },
+            Some(_) if ret.is_empty() => ret.push(Segment::Key(bare(&mut chars)?)),
+            Some(_) => return None,
+        }
+    }
+}
+
+/*
+ * bare() reads a key up to the next dot or bracket
+ */
+fn bare(chars:&mut Peekable<Chars>) -> Option<String> {
+    let mut ret = String::new();
+    while let Some(&c) = chars.peek() {
+        if matches!(c, '.' | '[' | ']') { break }
+        ret.push(c);
+        chars.next();
+    }
+    if ret.is_empty() { None } else { Some(ret) }
+}
+
+/*
+ * bracketed() reads an index or a quoted key, and the closing bracket
+ */
+fn bracketed(chars:&mut Peekable<Chars>) -> Option<Segment> {
+    let ret = if chars.peek() == Some(&'"') {
+        chars.next();
+        let mut key = String::new();
+        loop {
+            match chars.next()? {
+                '"' => break,
+                '\\' => key.push(chars.next()?),
+                c => key.push(c),
+            }
+        }
+        Segment::Key(key)
+    } else {
+        let mut digits = String::new();
+        while let Some(&c) = chars.peek() {
+            if !c.is_ascii_digit() { break }
+            digits.push(c);
+            chars.next();
+        }
+        Segment::Index(digits.parse().ok()?)
+    };
+    if chars.next()? == ']' { Some(ret) } else { None }
+}
+
+pub fn get<'a>(sn:&'a Shaun, path:&[Segment]) -> Option<&'a Shaun> {
+    path.iter().try_fold(sn, |sn, s| match (sn, s) {
+        (Shaun::Object(o), Segment::Key(k)) => o.get(k),
+        (Shaun::List(l), Segment::Index(n)) => l.get(*n),
+        _ => None,
+    })
+}
+
+pub fn get_mut<'a>(sn:&'a mut Shaun, path:&[Segment]) -> Option<&'a mut Shaun> {
+    path.iter().try_fold(sn, |sn, s| match (sn, s) {
+        (Shaun::Object(o), Segment::Key(k)) => o.get_mut(k),
+        (Shaun::List(l), Segment::Index(n)) => l.get_mut(*n),
+        _ => None,
+    })
+}
@@ -0,0 +1,527 @@
+use error::{ErrorKind, ParseError, Position};
+use diagnostic;
+use lexer::{Lexer, ParseAtom, ParseToken};
+use number::Num;
+use options::{Limit, ParseOptions};
+use datetime::{self, DateTime};
+use expr::{Expr, MAX_NESTING};
+
+use std::collections::VecDeque;
+use std::io::Read;
+
+/// An item of SHAUN data, as produced by a `Reader`.
+///
+/// Every `StartObject` and `StartList` is eventually followed by its
+/// matching `EndObject` and `EndList`, and every value inside an object
+/// is preceded by its `Key`. `Comment`s may appear anywhere. A
+/// `Separator` is found between documents, when the reader accepts
+/// several of them. An `Include` stands either for a value, or for
+/// entries of the object it appears in, and is left to a `Loader` to
+/// resolve. An `Anchor` names the value which follows it, so that an
+/// `Alias` can stand for a copy of that value. A `Variable` (`${name}`)
+/// stands for a value given by an `Interpolator`, and an `Expression`
+/// (`$(2 * timeout)`) for the value it computes. Durations written in
+/// decreasing units (`1h 30min`) are read as a single `Number`, in the
+/// smallest of them, unless separated by a comma.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Event {
+    StartObject,
+    EndObject,
+    StartList,
+    EndList,
+    Key(String),
+    Null,
+    Bool(bool),
+    Number(Num, String),
+    DateTime(DateTime),
+    String(String),
+    Comment(String),
+    Separator,
+    Include(String),
+    Anchor(String),
+    Alias(String),
+    Variable(String),
+    Expression(String),
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Container {
+    // the bool tells whether the object has explicit brackets,
+    // a root object may omit them
+    Object(bool),
+    List,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum State {
+    Start,
+    Key,
+    Value,
+    End,
+    // closes every container, the input ending too early
+    Closing,
+    Failed,
+}
+
+/// A pull parser reading SHAUN data from any `std::io::Read`.
+///
+/// The input is consumed as events are requested, so only the token
+/// being read and the nesting of the current value are kept in memory.
+///
+/// # Examples
+/// ```
+/// use shaun::{Event, Num, Reader};
+///
+/// let mut reader = Reader::new("speed: 10 km".as_bytes());
+/// assert_eq!(reader.next_event().unwrap(), Some(Event::StartObject));
+/// assert_eq!(reader.next_event().unwrap(), Some(Event::Key("speed".to_string())));
+/// assert_eq!(reader.next_event().unwrap(), Some(Event::Number(Num::UInt(10), "km".to_string())));
+/// assert_eq!(reader.next_event().unwrap(), Some(Event::EndObject));
+/// assert_eq!(reader.next_event().unwrap(), None);
+/// ```
+pub struct Reader<R : Read> {
+    lexer : Lexer<R>,
+    stack : Vec<Container>,
+    // number of keys or values of every container of the stack
+    counts : Vec<usize>,
+    state : State,
+    multi_document : bool,
+    documents : usize,
+    event_position : Position,
+    anchored : bool,
+    options : ParseOptions,
+    // error found while looking past the last event, for the next one
+    pending : VecDeque<ParseError>,
+}
+
+impl<R : Read> Reader<R> {
+    pub fn new(reader:R) -> Reader<R> {
+        Reader { lexer: Lexer::new(reader), stack: Vec::new(), counts: Vec::new(), state: State::Start, multi_document: false, documents: 0, event_position: Position::start(), anchored: false, options: ParseOptions::default(), pending: VecDeque::new() }
+    }
+
+    /// Accepts several documents separated by `---` lines, instead of
+    /// rejecting anything after the first one.
+    ///
+    /// # Examples
+    /// ```
+    /// use shaun::{Event, Reader};
+    ///
+    /// let reader = Reader::new("a: 1\n---\nb: 2".as_bytes()).multi_document();
+    /// let count = reader.filter(|e| e.as_ref().unwrap() == &Event::Separator).count();
+    /// assert_eq!(count, 1);
+    /// ```
+    pub fn multi_document(mut self) -> Reader<R> {
+        self.multi_document = true;
+        self
+    }
+
+    /// Reads HJSON: values which are neither numbers, keywords nor
+    /// delimited are strings running to the end of their line, and numbers
+    /// have no unit.
+    ///
+    /// # Examples
+    /// ```
+    /// use shaun::{Event, Reader};
+    ///
+    /// let mut reader = Reader::new("title: The Title, part 2\n".as_bytes()).hjson();
+    /// reader.next_event().unwrap();
+    /// reader.next_event().unwrap();
+    /// assert_eq!(reader.next_event().unwrap(), Some(Event::String("The Title, part 2".to_string())));
+    /// ```
+    pub fn hjson(mut self) -> Reader<R> {
+        self.options.hjson = true;
+        self
+    }
+
+    /// Reads with the given options.
+    pub fn with_options(reader:R, options:ParseOptions) -> Reader<R> {
+        let mut ret = Reader::new(reader);
+        if options.strict { ret.lexer.strict() }
+        if !options.allow_commas { ret.lexer.no_commas() }
+        if let Some(max) = options.max_input_size { ret.lexer.max_input_size(max) }
+        if let Some(max) = options.max_string_length { ret.lexer.max_string_length(max) }
+        ret.options = options;
+        ret
+    }
+
+    pub fn options(&self) -> &ParseOptions {
+        &self.options
+    }
+
+    /// Rejects characters which are not part of SHAUN, unknown escapes in
+    /// strings (`"\q"`) and invalid unicode ones, which are otherwise
+    /// skipped or read as the escaped text.
+    ///
+    /// # Examples
+    /// ```
+    /// use shaun::{ErrorKind, Reader};
+    ///
+    /// let mut reader = Reader::new("a: \"\\q\"".as_bytes()).strict();
+    /// let err = reader.find_map(|e| e.err()).unwrap();
+    /// assert!(matches!(err.kind(), ErrorKind::InvalidEscape(_)));
+    /// ```
+    pub fn strict(mut self) -> Reader<R> {
+        self.options.strict = true;
+        self.lexer.strict();
+        self
+    }
+
+    /// Position of the next char to be read.
+    pub fn current_position(&self) -> Position {
+        self.lexer.current_position()
+    }
+
+    /// Position where the last event read starts.
+    pub fn event_position(&self) -> Position {
+        self.event_position
+    }
+
+    /// Number of objects and lists currently open.
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Reads the next event, `None` once the document has been entirely read.
+    pub fn next_event(&mut self) -> Result<Option<Event>, ParseError> {
+        let ret = self.read_event();
+        let ret = match ret {
+            Ok(Some(e)) => self.count(&e).map(|_| Some(e)),
+            r => r,
+        };
+        if ret.is_err() { self.state = State::Failed }
+        ret
+    }
+
+    fn read_event(&mut self) -> Result<Option<Event>, ParseError> {
+        if let Some(e) = self.pending.pop_front() { return Err(e) }
+        if self.options.hjson && self.state == State::Value { self.lexer.quoteless() }
+        self.event_position = self.lexer.next_position()?;
+
+        if let Some(&ParseToken::Comment(_)) = self.lexer.peek()? {
+            if let Some((ParseToken::Comment(c), _)) = self.lexer.next()? {
+                return Ok(Some(Event::Comment(c)))
+            }
+        }
+
+        match self.state {
+            State::Start => self.read_start(),
+            State::Key => self.read_key(),
+            State::Value => self.read_value(),
+            State::End => match self.lexer.next()? {
+                None => Ok(None),
+                Some((ParseToken::Separator, _)) if self.multi_document => {
+                    self.state = State::Start;
+                    Ok(Some(Event::Separator))
+                },
+                Some((t, pos)) => Err(unexpected(t, pos)),
+            },
+            State::Closing => {
+                let ret = self.close();
+                if !self.stack.is_empty() { self.state = State::Closing }
+                Ok(Some(ret))
+            },
+            State::Failed => Ok(None),
+        }
+    }
+
+    /// After an error, skips what follows up to a key or a closing bracket
+    /// of the object being read, or up to a value of the list being read,
+    /// so that reading can go on. Objects and lists still open at the end
+    /// of the input are closed. Errors found while skipping are returned
+    /// by the next calls to `next_event`. Returns false for errors which
+    /// cannot be recovered from: I/O errors and exceeded limits.
+    ///
+    /// # Examples
+    /// ```
+    /// use shaun::{Event, Reader};
+    ///
+    /// let mut reader = Reader::new("a: 0x b: true".as_bytes());
+    /// let mut events = Vec::new();
+    /// loop {
+    ///     match reader.next_event() {
+    ///         Ok(Some(e)) => events.push(e),
+    ///         Ok(None) => break,
+    ///         Err(e) => assert!(reader.recover(&e)),
+    ///     }
+    /// }
+    /// assert_eq!(events[2], Event::Key("b".to_string()));
+    /// ```
+    pub fn recover(&mut self, error:&ParseError) -> bool {
+        if !recoverable(error) {
+            self.state = State::Failed;
+            return false
+        }
+        self.anchored = false;
+
+        loop {
+            let next = match self.lexer.peek() {
+                Ok(t) => t,
+                Err(e) => {
+                    let recovered = recoverable(&e);
+                    self.pending.push_back(e);
+                    if recovered { continue }
+                    self.state = State::Failed;
+                    return false
+                },
+            };
+
+            let resume = match (self.stack.last(), next) {
+                (_, None) if self.stack.is_empty() => Some(State::End),
+                (_, None) => Some(State::Closing),
+                (None, Some(&ParseToken::Separator)) => Some(State::End),
+                (Some(&Container::Object(_)), Some(&ParseToken::Name(_)))
+                    | (Some(&Container::Object(true)), Some(&ParseToken::Key('}')))
+                    | (Some(&Container::Object(false)), Some(&ParseToken::Separator)) => Some(State::Key),
+                (Some(&Container::List), Some(&ParseToken::Name(_)))
+                    | (Some(&Container::List), Some(&ParseToken::Key('}')))
+                    | (Some(&Container::List), Some(&ParseToken::Key(':'))) => None,
+                (Some(&Container::List), Some(_)) => Some(State::Value),
+                _ => None,
+            };
+
+            match resume {
+                Some(s) => { self.state = s; return true },
+                None => { let _ = self.lexer.next(); },
+            }
+        }
+    }
+
+    fn eof(&self) -> ParseError {
+        ParseError::new(ErrorKind::UnexpectedEof, self.current_position())
+    }
+
+    fn limit(&self, limit:Limit, max:usize) -> ParseError {
+        ParseError::new(ErrorKind::LimitExceeded(limit, max as u64), self.event_position)
+    }
+
+    fn open(&mut self, container:Container) -> Result<Event, ParseError> {
+        if let Some(max) = self.options.max_depth {
+            if self.stack.len() >= max { return Err(self.limit(Limit::Depth, max)) }
+        }
+        self.stack.push(container);
+        self.counts.push(0);
+        Ok(match container {
+            Container::Object(_) => { self.state = State::Key; Event::StartObject },
+            Container::List => { self.state = State::Value; Event::StartList },
+        })
+    }
+
+    /*
+     * count() counts the keys of the object, or the values of the list,
+     * which the event belongs to, against their limit
+     */
+    fn count(&mut self, event:&Event) -> Result<(), ParseError> {
+        let at = match *event {
+            // the container just opened belongs to the previous one
+            Event::StartObject | Event::StartList => self.stack.len().checked_sub(2),
+            Event::EndObject | Event::EndList | Event::Comment(_) | Event::Anchor(_) | Event::Separator => None,
+            _ => self.stack.len().checked_sub(1),
+        };
+        let i = match at {
+            Some(i) => i,
+            None => return Ok(()),
+        };
+
+        let (limit, max) = match (self.stack[i], event) {
+            (Container::Object(_), &Event::Key(_)) => (Limit::Keys, self.options.max_keys),
+            (Container::List, _) => (Limit::ListLength, self.options.max_list_length),
+            _ => return Ok(()),
+        };
+        self.counts[i] += 1;
+        match max {
+            Some(max) if self.counts[i] > max => Err(self.limit(limit, max)),
+            _ => Ok(()),
+        }
+    }
+
+    fn close(&mut self) -> Event {
+        self.counts.pop();
+        let ret = match self.stack.pop() {
+            Some(Container::List) => Event::EndList,
+            _ => Event::EndObject,
+        };
+        self.next_state();
+        ret
+    }
+
+    fn next_state(&mut self) {
+        self.state = match self.stack.last() {
+            Some(&Container::Object(_)) => State::Key,
+            Some(&Container::List) => State::Value,
+            None => State::End,
+        }
+    }
+
+    fn read_start(&mut self) -> Result<Option<Event>, ParseError> {
+        if self.multi_document {
+            match self.lexer.peek()? {
+                // a leading separator only marks the start of the first document
+                Some(&ParseToken::Separator) if self.documents == 0 => { self.lexer.next()?; },
+                // nor is there a document after a trailing one
+                None if self.documents > 0 => { self.state = State::End; return Ok(None) },
+                _ => (),
+            }
+        }
+
+        self.documents += 1;
+        let braced = self.lexer.peek()? == Some(&ParseToken::Key('{'));
+        if braced { self.lexer.next()?; }
+        Ok(Some(self.open(Container::Object(braced))?))
+    }
+
+    fn read_key(&mut self) -> Result<Option<Event>, ParseError> {
+        let braced = self.stack.last() == Some(&Container::Object(true));
+
+        // a root object without brackets also ends with its document
+        if !braced && self.lexer.peek()? == Some(&ParseToken::Separator) {
+            return Ok(Some(self.close()))
+        }
+
+        match self.lexer.next()? {
+            Some((ParseToken::Name(k), _)) => {
+                self.state = State::Value;
+                Ok(Some(Event::Key(k)))
+            },
+            Some((ParseToken::Key('}'), _)) if braced => Ok(Some(self.close())),
+            None if !braced => Ok(Some(self.close())),
+            Some((ParseToken::Include, _)) => Ok(Some(Event::Include(self.read_include()?))),
+            Some((t, pos)) => Err(unexpected(t, pos)),
+            None => Err(self.eof()),
+        }
+    }
+
+    fn read_value(&mut self) -> Result<Option<Event>, ParseError> {
+        let in_list = self.stack.last() == Some(&Container::List);
+        let anchored = ::std::mem::replace(&mut self.anchored, false);
+        let ret = match self.lexer.next()? {
+            Some((ParseToken::Key('{'), _)) => return Ok(Some(self.open(Container::Object(true))?)),
+            Some((ParseToken::Key('['), _)) => return Ok(Some(self.open(Container::List)?)),
+            Some((ParseToken::Key(']'), _)) if in_list && !anchored => return Ok(Some(self.close())),
+            Some((ParseToken::Atom(ParseAtom::Number(n)), _)) => {
+                let unit = self.read_unit()?;
+                let (n, unit) = self.read_duration(n, unit)?;
+                Event::Number(n, unit)
+            },
+            Some((ParseToken::Atom(ParseAtom::DateTime(d)), _)) => Event::DateTime(d),
+            Some((ParseToken::Atom(ParseAtom::String(s)), _)) => Event::String(s),
+            Some((ParseToken::Atom(ParseAtom::Bool(b)), _)) => Event::Bool(b),
+            Some((ParseToken::Atom(ParseAtom::Null), _)) => Event::Null,
+            Some((ParseToken::Include, _)) => Event::Include(self.read_include()?),
+            // the anchored value comes next
+            Some((ParseToken::Anchor(a), _)) if !anchored => {
+                self.anchored = true;
+                return Ok(Some(Event::Anchor(a)))
+            },
+            Some((ParseToken::Alias(a), _)) => Event::Alias(a),
+            Some((ParseToken::Variable(v), _)) => Event::Variable(v),
+            Some((ParseToken::Expression(e), pos)) => {
+                // expressions are evaluated later, but nest within limits
+                if let Some(max) = self.options.max_depth {
+                    if let Err(k @ ErrorKind::LimitExceeded(..)) = Expr::parse(&e, max.min(MAX_NESTING)) {
+                        return Err(ParseError::new(k, pos))
+                    }
+                }
+                Event::Expression(e)
+            },
+            // a bare word is a string, unless a typo of a keyword in strict mode
+            Some((ParseToken::Id(id), pos)) if self.options.strict && diagnostic::suggestion(&id).is_some() => {
+                return Err(unexpected(ParseToken::Id(id), pos))
+            },
+            Some((ParseToken::Id(id), _)) => Event::String(id),
+            Some((t, pos)) => {
+                let err = ParseError::new(ErrorKind::UnexpectedToken(t.to_string()), pos);
+                // keys and closing brackets are read again, when recovering
+                if matches!(t, ParseToken::Name(_) | ParseToken::Key('}') | ParseToken::Key(']')) {
+                    self.lexer.push_back(t, pos)
+                }
+                return Err(err)
+            },
+            None => return Err(self.eof()),
+        };
+
+        self.next_state();
+        Ok(Some(ret))
+    }
+
+    /*
+     * `@include` is followed by the path of the file
+     */
+    fn read_include(&mut self) -> Result<String, ParseError> {
+        match self.lexer.next()? {
+            Some((ParseToken::Atom(ParseAtom::String(path)), _)) => Ok(path),
+            Some((t, pos)) => Err(unexpected(t, pos)),
+            None => Err(self.eof()),
+        }
+    }
+
+    /*
+     * peek_after() peeks at the token following a value being read, an
+     * error being left for the next event, rather than losing the value
+     */
+    fn peek_after(&mut self) -> Result<Option<&ParseToken>, ParseError> {
+        if let Err(e) = self.lexer.peek() {
+            self.pending.push_back(e);
+            return Ok(None)
+        }
+        self.lexer.peek()
+    }
+
+    /*
+     * A number may be directly followed by its unit
+     */
+    fn read_unit(&mut self) -> Result<String, ParseError> {
+        if self.options.hjson { return Ok(String::new()) }
+        if let Some(&ParseToken::Id(_)) = self.peek_after()? {
+            if let Some((ParseToken::Id(unit), _)) = self.lexer.next()? {
+                return Ok(unit)
+            }
+        }
+        Ok(String::new())
+    }
+
+    /*
+     * A duration may go on with numbers of smaller units of duration
+     * (`1h 30min`), summed in the smallest unit, in objects and lists
+     * alike. Any other number, or one following a comma, is a value of
+     * its own (`[1h, 30min]`).
+     */
+    fn read_duration(&mut self, mut n:Num, mut unit:String) -> Result<(Num, String), ParseError> {
+        while let Some(f) = datetime::nanoseconds(&unit) {
+            if !matches!(self.peek_after()?, Some(&ParseToken::Atom(ParseAtom::Number(_)))) || self.lexer.after_comma() { break }
+            let (m, pos) = match self.lexer.next()? {
+                Some((ParseToken::Atom(ParseAtom::Number(m)), pos)) => (m, pos),
+                _ => break,
+            };
+            let next = match self.peek_after()? {
+                Some(ParseToken::Id(u)) if datetime::nanoseconds(u).is_some_and(|g| g < f) => u.clone(),
+                _ => {
+                    self.lexer.push_back(ParseToken::Atom(ParseAtom::Number(m)), pos);
+                    break
+                },
+            };
+            self.lexer.next()?;
+            if let Some((sum, u)) = datetime::add_durations(&n, &unit, &m, &next) { n = sum; unit = u }
+        }
+        Ok((n, unit))
+    }
+}
+
+fn recoverable(error:&ParseError) -> bool {
+    !matches!(error.kind(), ErrorKind::Io(_) | ErrorKind::LimitExceeded(_, _))
+}
+
+fn unexpected(t:ParseToken, pos:Position) -> ParseError {
+    ParseError::new(ErrorKind::UnexpectedToken(t.to_string()), pos)
+}
+
+impl<R : Read> Iterator for Reader<R> {
+    type Item = Result<Event, ParseError>;
+
+    fn next(&mut self) -> Option<Result<Event, ParseError>> {
+        match self.next_event() {
+            Ok(Some(e)) => Some(Ok(e)),
+            Ok(None) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
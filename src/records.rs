@@ -0,0 +1,117 @@
+use shaun_type::Shaun;
+use error::{ErrorKind, ParseError, Position};
+use parser::parse_reader;
+use writer::Writer;
+
+use std::io;
+use std::io::{BufRead, BufReader, Read, Write};
+
+/// Iterator over a stream holding one SHAUN object per line, as returned
+/// by `records`.
+///
+/// Lines are read lazily, one at a time. Blank lines are skipped. A
+/// malformed record yields an error positioned at its line in the stream,
+/// and iteration resumes with the next line.
+pub struct Records<R : BufRead> {
+    reader : R,
+    buffer : Vec<u8>,
+    index : usize,
+    line : u32,
+    failed : bool,
+}
+
+/// Reads a stream of newline-delimited SHAUN records.
+///
+/// # Examples
+/// ```
+/// use shaun::records;
+///
+/// let input = "{ id: 1 }\n{ id: \n{ id: 3 }\n";
+/// let ids : Vec<bool> = records(input.as_bytes()).map(|r| r.is_ok()).collect();
+/// assert_eq!(ids, vec![true, false, true]);
+/// ```
+pub fn records<R : Read>(reader:R) -> Records<BufReader<R>> {
+    Records::new(BufReader::new(reader))
+}
+
+impl<R : BufRead> Records<R> {
+    pub fn new(reader:R) -> Records<R> {
+        Records { reader, buffer: Vec::new(), index: 0, line: 0, failed: false }
+    }
+
+    /// Index of the last record read, counting malformed ones, starting at 0.
+    pub fn index(&self) -> usize {
+        self.index.saturating_sub(1)
+    }
+
+    /// Line of the last record read, starting at 1.
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+}
+
+impl<R : BufRead> Iterator for Records<R> {
+    type Item = Result<Shaun, ParseError>;
+
+    fn next(&mut self) -> Option<Result<Shaun, ParseError>> {
+        if self.failed { return None }
+
+        loop {
+            self.buffer.clear();
+            match self.reader.read_until(b'\n', &mut self.buffer) {
+                Ok(0) => return None,
+                Ok(_) => self.line += 1,
+                Err(e) => {
+                    self.failed = true;
+                    let pos = Position { line: self.line + 1, column: 1 };
+                    return Some(Err(ParseError::new(ErrorKind::Io(e), pos)))
+                },
+            }
+
+            if self.buffer.iter().all(|b| (*b as char).is_whitespace()) { continue }
+
+            self.index += 1;
+            let line = self.line;
+            return Some(parse_reader(&self.buffer[..]).map_err(|e| {
+                let column = e.position().map_or(1, |p| p.column);
+                ParseError::new(e.into_kind(), Position { line, column })
+            }))
+        }
+    }
+}
+
+/// Writes Shaun objects as newline-delimited records, each one on a
+/// single line, readable back with `records`.
+///
+/// # Examples
+/// ```
+/// use shaun::RecordWriter;
+///
+/// let mut w = RecordWriter::new(Vec::new());
+/// w.write(&shaun::parse_str("msg: \"two\nlines\"")).unwrap();
+/// assert_eq!(w.into_inner(), b"{msg: \"two\\nlines\"}\n");
+/// ```
+pub struct RecordWriter<W : Write> {
+    out : W,
+}
+
+impl<W : Write> RecordWriter<W> {
+    pub fn new(out:W) -> RecordWriter<W> {
+        RecordWriter { out }
+    }
+
+    /// Writes a record, which must be an object.
+    pub fn write(&mut self, record:&Shaun) -> io::Result<()> {
+        if !record.is_object() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "RecordWriter: records must be objects"))
+        }
+        let mut w = Writer::compact(&mut self.out);
+        w.value(record)?;
+        w.finish()?;
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
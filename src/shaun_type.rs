@@ -1,34 +1,162 @@
 use visitor::Visitor;
+use number::Num;
+use datetime::{self, DateTime};
+use path::{self, PathDisplay, Segment};
 
 use std::collections::HashMap;
 use std::convert::From;
 use std::convert::TryInto;
+use std::convert::TryFrom;
 use std::clone::Clone;
-use std::ops::Index;
+use std::error::Error;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use std::time::Duration;
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Shaun {
     Null,
-    Number(f64,String),
+    Number(Num,String),
     String(String),
     Bool(bool),
     List(Vec<Shaun>),
     Object(HashMap<String,Shaun>),
+    DateTime(DateTime),
 }
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
-pub enum ShaunError {
+/// What went wrong when looking up or converting a value.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub enum ShaunErrorKind {
     NotANumber,
     NotAString,
     NotABool,
     NotAList,
     NotAnObject,
-    OutOfBound,
-    AttributeNotFound,
+    /// The index asked for, past the end of the list.
+    OutOfBound(usize),
+    /// The key asked for, missing from the object.
+    AttributeNotFound(String),
+    NotAnInteger,
+    NotADuration,
+    NotADateTime,
+    /// The type the number could not be converted to, such as `u8`.
+    OutOfRange(String),
+    /// The path which could not be read, such as `a..b`.
+    InvalidPath(String),
+}
+
+/// Error returned when a value is missing or not of the type asked for,
+/// with the path of the value and what was found instead, if known.
+///
+/// # Examples
+/// ```
+/// use std::convert::TryFrom;
+/// use shaun::ShaunErrorKind;
+///
+/// let sn = shaun::parse_str("port: \"80\"");
+/// let err = u16::try_from(sn.get("port").unwrap().clone()).unwrap_err().at_path("port");
+/// assert_eq!(err.kind(), &ShaunErrorKind::NotANumber);
+/// assert_eq!(err.to_string(), "expected a number, found a string at `port`");
+/// assert_eq!(sn.get("host").unwrap_err().to_string(), "key `host` not found");
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct ShaunError {
+    kind : ShaunErrorKind,
+    path : String,
+    found : Option<String>,
+}
+
+impl ShaunError {
+    pub fn new(kind:ShaunErrorKind) -> ShaunError {
+        ShaunError { kind, path: String::new(), found: None }
+    }
+
+    /*
+     * mismatch() is the error of a value which is not of the type asked
+     * for
+     */
+    fn mismatch(kind:ShaunErrorKind, found:&Shaun) -> ShaunError {
+        ShaunError { found: Some(found.type_name().to_string()), ..ShaunError::new(kind) }
+    }
+
+    pub fn kind(&self) -> &ShaunErrorKind {
+        &self.kind
+    }
+
+    pub fn into_kind(self) -> ShaunErrorKind {
+        self.kind
+    }
+
+    /// Path of the value which caused the error, such as `servers[0].port`,
+    /// empty if unknown.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// What was found instead of the value asked for: a type such as
+    /// `a string`, or a number which is not an integer or is out of range.
+    pub fn found(&self) -> Option<&str> {
+        self.found.as_deref()
+    }
+
+    /// Sets the path of the value which caused the error, unless already
+    /// known.
+    pub fn at_path<S : Into<String>>(mut self, path:S) -> ShaunError {
+        if self.path.is_empty() { self.path = path.into() }
+        self
+    }
+
+    fn suffix(&self, f:&mut fmt::Formatter, message:fmt::Arguments) -> fmt::Result {
+        write!(f, "{}", message)?;
+        if self.path.is_empty() { Ok(()) } else { write!(f, " at `{}`", self.path) }
+    }
+}
+
+impl fmt::Display for ShaunError {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        let expected = match self.kind {
+            ShaunErrorKind::NotANumber => "a number",
+            ShaunErrorKind::NotAString => "a string",
+            ShaunErrorKind::NotABool => "a boolean",
+            ShaunErrorKind::NotAList => "a list",
+            ShaunErrorKind::NotAnObject => "an object",
+            ShaunErrorKind::NotAnInteger => "an integer",
+            ShaunErrorKind::NotADuration => "a duration",
+            ShaunErrorKind::NotADateTime => "a date",
+            ShaunErrorKind::OutOfBound(i) => return self.suffix(f, format_args!("index {} out of bounds", i)),
+            ShaunErrorKind::AttributeNotFound(ref k) => return self.suffix(f, format_args!("key `{}` not found", k)),
+            ShaunErrorKind::InvalidPath(ref p) => return self.suffix(f, format_args!("invalid path `{}`", p)),
+            ShaunErrorKind::OutOfRange(ref t) => return match self.found {
+                Some(ref n) => self.suffix(f, format_args!("{} is out of the range of {}", n, t)),
+                None => self.suffix(f, format_args!("number out of the range of {}", t)),
+            },
+        };
+        match self.found {
+            Some(ref found) => self.suffix(f, format_args!("expected {}, found {}", expected, found)),
+            None => self.suffix(f, format_args!("expected {}", expected)),
+        }
+    }
 }
 
+impl Error for ShaunError {}
+
 impl Shaun {
+    /// The type of the value, as written in error messages: `null`,
+    /// `a number`, `a string`, `a boolean`, `a list`, `an object` or
+    /// `a date`.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Shaun::Null => "null",
+            Shaun::Number(_, _) => "a number",
+            Shaun::String(_) => "a string",
+            Shaun::Bool(_) => "a boolean",
+            Shaun::List(_) => "a list",
+            Shaun::Object(_) => "an object",
+            Shaun::DateTime(_) => "a date",
+        }
+    }
+
     pub fn is_object(&self) -> bool {
         match self {
             &Shaun::Object(_) => true,
@@ -71,31 +199,117 @@ impl Shaun {
         }
     }
 
+    pub fn is_datetime(&self) -> bool {
+        matches!(self, Shaun::DateTime(_))
+    }
+
+    /// The date or time, if the value is one.
+    pub fn as_datetime(&self) -> Option<&DateTime> {
+        match self {
+            Shaun::DateTime(d) => Some(d),
+            _ => None,
+        }
+    }
+
+    /// The duration, if the value is a non-negative number of a unit of
+    /// duration: `ns`, `us` (or `µs`), `ms`, `s`, `min`, `h`, `d` or `w`.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// let sn = shaun::parse_str("timeout: 1h 30min");
+    /// assert_eq!(sn.get("timeout").unwrap().as_duration(), Some(Duration::from_secs(5400)));
+    /// ```
+    pub fn as_duration(&self) -> Option<Duration> {
+        match self {
+            Shaun::Number(n, u) => datetime::duration(n, u),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Shaun::Number(n, _) => Some(n.as_f64()),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Shaun::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Shaun::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Shaun]> {
+        match self {
+            Shaun::List(l) => Some(l),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&HashMap<String, Shaun>> {
+        match self {
+            Shaun::Object(o) => Some(o),
+            _ => None,
+        }
+    }
+
+    /// The unit of a number, empty if it has none.
+    pub fn unit(&self) -> Option<&str> {
+        match self {
+            Shaun::Number(_, u) => Some(u),
+            _ => None,
+        }
+    }
+
+    /// The value and unit of a number.
+    ///
+    /// # Examples
+    /// ```
+    /// let sn = shaun::parse_str("width: 12.5 cm");
+    /// assert_eq!(sn.get("width").unwrap().as_number(), Some((12.5, "cm")));
+    /// ```
+    pub fn as_number(&self) -> Option<(f64, &str)> {
+        match self {
+            Shaun::Number(n, u) => Some((n.as_f64(), u)),
+            _ => None,
+        }
+    }
+
     pub fn to_vec(&self) -> Result<&Vec<Shaun>, ShaunError> {
         match self {
             &Shaun::List(ref v) => Ok(v),
-            _ => Err(ShaunError::NotAList),
+            _ => Err(ShaunError::mismatch(ShaunErrorKind::NotAList, self)),
         }
     }
 
     pub fn to_map(&self) -> Result<&HashMap<String, Shaun>, ShaunError> {
         match self {
             &Shaun::Object(ref o) => Ok(o),
-            _ => Err(ShaunError::NotAnObject),
+            _ => Err(ShaunError::mismatch(ShaunErrorKind::NotAnObject, self)),
         }
     }
 
     pub fn to_mut_vec(&mut self) -> Result<&mut Vec<Shaun>, ShaunError> {
         match self {
             &mut Shaun::List(ref mut v) => Ok(v),
-            _ => Err(ShaunError::NotAList),
+            _ => Err(ShaunError::mismatch(ShaunErrorKind::NotAList, self)),
         }
     }
 
     pub fn to_mut_map(&mut self) -> Result<&mut HashMap<String, Shaun>, ShaunError> {
         match self {
             &mut Shaun::Object(ref mut o) => Ok(o),
-            _ => Err(ShaunError::NotAnObject),
+            _ => Err(ShaunError::mismatch(ShaunErrorKind::NotAnObject, self)),
         }
     }
 
@@ -103,9 +317,9 @@ impl Shaun {
         match self {
             &Shaun::Object(ref o) => {
                 let s = String::from(id);
-                o.get(&s).ok_or_else(move || ShaunError::AttributeNotFound)
+                o.get(&s).ok_or_else(move || ShaunError::new(ShaunErrorKind::AttributeNotFound(s)))
             },
-            _ => Err(ShaunError::NotAnObject),
+            _ => Err(ShaunError::mismatch(ShaunErrorKind::NotAnObject, self)),
         }
     }
 
@@ -113,9 +327,9 @@ impl Shaun {
         match self {
             &mut Shaun::Object(ref mut o) => {
                 let s = String::from(id);
-                o.get_mut(&s).ok_or_else(move || ShaunError::AttributeNotFound)
+                o.get_mut(&s).ok_or_else(move || ShaunError::new(ShaunErrorKind::AttributeNotFound(s)))
             },
-            _ => Err(ShaunError::NotAnObject),
+            _ => Err(ShaunError::mismatch(ShaunErrorKind::NotAnObject, self)),
         }
     }
 
@@ -123,10 +337,10 @@ impl Shaun {
 	let i : usize = index.into();
         match self {
             &Shaun::List(ref v) => {
-                if v.len() <= i { Err(ShaunError::OutOfBound) }
+                if v.len() <= i { Err(ShaunError::new(ShaunErrorKind::OutOfBound(i))) }
                 else { Ok(&v[i]) }
             },
-            _ => Err(ShaunError::NotAList)
+            _ => Err(ShaunError::mismatch(ShaunErrorKind::NotAList, self))
         }
     }
 
@@ -134,11 +348,110 @@ impl Shaun {
 	let i : usize = index.into();
         match self {
             &mut Shaun::List(ref mut v) => {
-                if v.len() <= i { Err(ShaunError::OutOfBound) }
+                if v.len() <= i { Err(ShaunError::new(ShaunErrorKind::OutOfBound(i))) }
                 else { Ok(&mut v[i]) }
             },
-            _ => Err(ShaunError::NotAList)
+            _ => Err(ShaunError::mismatch(ShaunErrorKind::NotAList, self))
+        }
+    }
+
+    /// The value at a path such as `servers[0].port`, failing with the
+    /// path of the first key or index missing, or of the first value which
+    /// is not an object or a list as the path goes on. Keys containing
+    /// dots or brackets are quoted in brackets, as in `["server.name"]`.
+    ///
+    /// # Examples
+    /// ```
+    /// let sn = shaun::parse_str("servers: [{ port: 80 }] \"server.name\": \"api\"");
+    /// assert_eq!(sn.require("servers[0].port").unwrap(), &shaun::Shaun::from(80));
+    /// assert_eq!(sn.require("[\"server.name\"]").unwrap(), &shaun::Shaun::from("api"));
+    /// assert_eq!(sn.require("servers[0].host").unwrap_err().to_string(), "key `host` not found at `servers[0].host`");
+    /// assert_eq!(sn.require("servers.port").unwrap_err().to_string(), "expected an object, found a list at `servers`");
+    /// assert_eq!(sn.require("servers[").unwrap_err().to_string(), "invalid path `servers[`");
+    /// ```
+    pub fn require(&self, path:&str) -> Result<&Shaun, ShaunError> {
+        let segments = path::parse(path).ok_or_else(|| ShaunError::new(ShaunErrorKind::InvalidPath(path.to_string())))?;
+        let mut sn = self;
+        for (i, s) in segments.iter().enumerate() {
+            let kind = match (sn, s) {
+                (Shaun::Object(o), Segment::Key(k)) => match o.get(k) {
+                    Some(v) => { sn = v; continue },
+                    None => ShaunErrorKind::AttributeNotFound(k.clone()),
+                },
+                (Shaun::List(l), Segment::Index(n)) => match l.get(*n) {
+                    Some(v) => { sn = v; continue },
+                    None => ShaunErrorKind::OutOfBound(*n),
+                },
+                (_, Segment::Key(_)) => {
+                    return Err(ShaunError::mismatch(ShaunErrorKind::NotAnObject, sn).at_path(PathDisplay(&segments[..i]).to_string()))
+                },
+                (_, Segment::Index(_)) => {
+                    return Err(ShaunError::mismatch(ShaunErrorKind::NotAList, sn).at_path(PathDisplay(&segments[..i]).to_string()))
+                },
+            };
+            return Err(ShaunError::new(kind).at_path(PathDisplay(&segments[..=i]).to_string()))
         }
+        Ok(sn)
+    }
+
+    /// The value at a path converted to `T`, or `default` if there is no
+    /// value at the path. A value which cannot be converted is an error
+    /// rather than replaced by the default, so that typos in data are not
+    /// silently ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// let sn = shaun::parse_str("host: \"localhost\"");
+    /// assert_eq!(sn.get_or("port", 8080).unwrap(), 8080);
+    /// assert_eq!(sn.get_or("host", String::new()).unwrap(), "localhost");
+    /// assert!(sn.get_or("host", 8080).is_err());
+    /// ```
+    pub fn get_or<T>(&self, path:&str, default:T) -> Result<T, ShaunError> where T : for<'a> TryFrom<&'a Shaun, Error = ShaunError> {
+        match self.require(path) {
+            Ok(sn) => T::try_from(sn).map_err(|e| e.at_path(path)),
+            Err(ref e) if is_missing(e.kind()) => Ok(default),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn get_f64(&self, path:&str) -> Result<f64, ShaunError> {
+        f64::try_from(self.require(path)?).map_err(|e| e.at_path(path))
+    }
+
+    pub fn get_str(&self, path:&str) -> Result<&str, ShaunError> {
+        <&str>::try_from(self.require(path)?).map_err(|e| e.at_path(path))
+    }
+
+    pub fn get_bool(&self, path:&str) -> Result<bool, ShaunError> {
+        bool::try_from(self.require(path)?).map_err(|e| e.at_path(path))
+    }
+
+    /// The duration at a path, as `as_duration` reads it.
+    pub fn get_duration(&self, path:&str) -> Result<Duration, ShaunError> {
+        let sn = self.require(path)?;
+        sn.as_duration().ok_or_else(|| {
+            let found = match sn {
+                Shaun::Number(n, u) if !u.is_empty() => format!("{} {}", n, u),
+                Shaun::Number(n, _) => n.to_string(),
+                _ => sn.type_name().to_string(),
+            };
+            ShaunError { found: Some(found), ..ShaunError::new(ShaunErrorKind::NotADuration) }.at_path(path)
+        })
+    }
+
+    /// The list at a path, each value converted to `T`.
+    ///
+    /// # Examples
+    /// ```
+    /// let sn = shaun::parse_str("hosts: [\"a\" \"b\"] ports: [80 \"443\"]");
+    /// assert_eq!(sn.get_list_of::<String>("hosts").unwrap(), vec!["a", "b"]);
+    /// assert_eq!(sn.get_list_of::<u16>("ports").unwrap_err().path(), "ports[1]");
+    /// ```
+    pub fn get_list_of<T>(&self, path:&str) -> Result<Vec<T>, ShaunError> where T : for<'a> TryFrom<&'a Shaun, Error = ShaunError> {
+        let list = <&[Shaun]>::try_from(self.require(path)?).map_err(|e| e.at_path(path))?;
+        list.iter().enumerate()
+            .map(|(i, sn)| T::try_from(sn).map_err(|e| e.at_path(format!("{}[{}]", path, i))))
+            .collect()
     }
 
     pub fn visit_with<T>(&self, visitor:&mut T) where T : Visitor {
@@ -149,28 +462,116 @@ impl Shaun {
             &Shaun::Bool(ref v) => visitor.visit_bool(v),
             &Shaun::List(ref l) => visitor.visit_list(l),
             &Shaun::Object(ref o) => visitor.visit_object(o),
+            &Shaun::DateTime(ref d) => visitor.visit_datetime(d),
+        }
+    }
+}
+
+/// Reads the value at an index of a list, Null if there is none.
+///
+/// Unlike `at`, indexing never fails: indexing past the end of a list, or
+/// a value which is not a list, gives Null, so that `sn["servers"][0]["port"]`
+/// reads nested values without checking each step. Use `at`, `get` or
+/// `require` to know why a value is missing.
+///
+/// # Examples
+/// ```
+/// let sn = shaun::parse_str("servers: [{ port: 80 }]");
+/// assert_eq!(sn["servers"][0]["port"], shaun::Shaun::from(80));
+/// assert!(sn["servers"][1]["port"].is_null());
+/// assert!(sn["servers"]["port"].is_null());
+/// ```
+impl Index<usize> for Shaun {
+    type Output = Shaun;
+    fn index(&self, index:usize) -> &Shaun {
+        match self {
+            Shaun::List(v) => v.get(index).unwrap_or(&Shaun::Null),
+            _ => &Shaun::Null,
         }
     }
 }
 
-impl<T : Into<usize>> Index<T> for Shaun {
+/// Gives the value at an index of a list to assign it, appending Null
+/// values up to the index if the list is too short. Null becomes an empty
+/// list first, so that nested lists are created on assignment.
+///
+/// # Panics
+/// If the value is neither a list nor Null.
+impl IndexMut<usize> for Shaun {
+    fn index_mut(&mut self, index:usize) -> &mut Shaun {
+        if self.is_null() { *self = Shaun::List(Vec::new()) }
+        match self {
+            Shaun::List(v) => {
+                if v.len() <= index { v.resize(index + 1, Shaun::Null) }
+                &mut v[index]
+            },
+            _ => panic!("cannot index {} with [{}]", self.type_name(), index),
+        }
+    }
+}
+
+/// Reads the value of a key of an object, Null if there is none.
+///
+/// Unlike `get`, indexing never fails: a missing key, or a value which is
+/// not an object, gives Null.
+impl<'a> Index<&'a str> for Shaun {
     type Output = Shaun;
-    fn index(&self, index:T) -> &Shaun {
-        if let &Shaun::List(ref v) = self {
-            let id : usize = index.into();
-            &v[id]
-        } else {
-            &Shaun::Null
+    fn index(&self, key:&'a str) -> &Shaun {
+        match self {
+            Shaun::Object(o) => o.get(key).unwrap_or(&Shaun::Null),
+            _ => &Shaun::Null,
+        }
+    }
+}
+
+/// Gives the value of a key of an object to assign it, inserting Null if
+/// the key is missing. Null becomes an empty object first, so that nested
+/// objects are created on assignment.
+///
+/// # Examples
+/// ```
+/// let mut sn = shaun::Shaun::Null;
+/// sn["server"]["ports"][1] = 443.into();
+/// assert_eq!(sn.require("server.ports").unwrap(), &shaun::Shaun::from(vec![shaun::Shaun::Null, 443.into()]));
+/// ```
+///
+/// # Panics
+/// If the value is neither an object nor Null.
+impl<'a> IndexMut<&'a str> for Shaun {
+    fn index_mut(&mut self, key:&'a str) -> &mut Shaun {
+        if self.is_null() { *self = Shaun::Object(HashMap::new()) }
+        match self {
+            Shaun::Object(o) => o.entry(key.to_string()).or_insert(Shaun::Null),
+            _ => panic!("cannot index {} with `{}`", self.type_name(), key),
         }
     }
 }
 
+macro_rules! string_index {
+    ($([$($l:lifetime)*] $t:ty),*) => {$(
+        impl<$($l)*> Index<$t> for Shaun {
+            type Output = Shaun;
+            fn index(&self, key:$t) -> &Shaun {
+                &self[key.as_str()]
+            }
+        }
+
+        impl<$($l)*> IndexMut<$t> for Shaun {
+            fn index_mut(&mut self, key:$t) -> &mut Shaun {
+                &mut self[key.as_str()]
+            }
+        }
+    )*}
+}
+
+string_index! { [] String, ['a] &'a String }
+
 impl TryInto<f64> for Shaun {
     type Error = ShaunError;
     fn try_into(self) -> Result<f64, ShaunError> {
         match self {
-            Shaun::Number(x,_) => Ok(x),
-            _ => Err(ShaunError::NotANumber),
+            Shaun::Number(x,_) => Ok(x.as_f64()),
+            sn => Err(ShaunError::mismatch(ShaunErrorKind::NotANumber, &sn)),
         }
     }
 }
@@ -180,7 +581,7 @@ impl TryInto<String> for Shaun {
     fn try_into(self) -> Result<String, ShaunError> {
         match self {
             Shaun::String(s) => Ok(s),
-            _ => Err(ShaunError::NotAString),
+            sn => Err(ShaunError::mismatch(ShaunErrorKind::NotAString, &sn)),
         }
     }
 }
@@ -190,7 +591,7 @@ impl TryInto<bool> for Shaun {
     fn try_into(self) -> Result<bool, ShaunError> {
         match self {
             Shaun::Bool(s) => Ok(s),
-            _ => Err(ShaunError::NotABool),
+            sn => Err(ShaunError::mismatch(ShaunErrorKind::NotABool, &sn)),
         }
     }
 }
@@ -200,7 +601,7 @@ impl TryInto<Vec<Shaun>> for Shaun {
     fn try_into(self) -> Result<Vec<Shaun>, ShaunError> {
         match self {
             Shaun::List(v) => Ok(v),
-            _ => Err(ShaunError::NotAList),
+            sn => Err(ShaunError::mismatch(ShaunErrorKind::NotAList, &sn)),
         }
     }
 }
@@ -210,23 +611,183 @@ impl TryInto<HashMap<String, Shaun>> for Shaun {
     fn try_into(self) -> Result<HashMap<String, Shaun>, ShaunError> {
         match self {
             Shaun::Object(o) => Ok(o),
-            _ => Err(ShaunError::NotAnObject),
+            sn => Err(ShaunError::mismatch(ShaunErrorKind::NotAnObject, &sn)),
+        }
+    }
+}
+
+/*
+ * Conversions of borrowed values, reading data without cloning it
+ */
+impl<'a> TryFrom<&'a Shaun> for f64 {
+    type Error = ShaunError;
+    fn try_from(sn:&'a Shaun) -> Result<f64, ShaunError> {
+        sn.as_f64().ok_or_else(|| ShaunError::mismatch(ShaunErrorKind::NotANumber, sn))
+    }
+}
+
+impl<'a> TryFrom<&'a Shaun> for bool {
+    type Error = ShaunError;
+    fn try_from(sn:&'a Shaun) -> Result<bool, ShaunError> {
+        sn.as_bool().ok_or_else(|| ShaunError::mismatch(ShaunErrorKind::NotABool, sn))
+    }
+}
+
+impl<'a> TryFrom<&'a Shaun> for &'a str {
+    type Error = ShaunError;
+    fn try_from(sn:&'a Shaun) -> Result<&'a str, ShaunError> {
+        sn.as_str().ok_or_else(|| ShaunError::mismatch(ShaunErrorKind::NotAString, sn))
+    }
+}
+
+impl<'a> TryFrom<&'a Shaun> for &'a Num {
+    type Error = ShaunError;
+    fn try_from(sn:&'a Shaun) -> Result<&'a Num, ShaunError> {
+        match sn {
+            Shaun::Number(n, _) => Ok(n),
+            _ => Err(ShaunError::mismatch(ShaunErrorKind::NotANumber, sn)),
         }
     }
 }
 
+impl<'a> TryFrom<&'a Shaun> for &'a [Shaun] {
+    type Error = ShaunError;
+    fn try_from(sn:&'a Shaun) -> Result<&'a [Shaun], ShaunError> {
+        sn.as_list().ok_or_else(|| ShaunError::mismatch(ShaunErrorKind::NotAList, sn))
+    }
+}
+
+impl<'a> TryFrom<&'a Shaun> for &'a HashMap<String, Shaun> {
+    type Error = ShaunError;
+    fn try_from(sn:&'a Shaun) -> Result<&'a HashMap<String, Shaun>, ShaunError> {
+        sn.as_object().ok_or_else(|| ShaunError::mismatch(ShaunErrorKind::NotAnObject, sn))
+    }
+}
+
+impl<'a> TryFrom<&'a Shaun> for &'a DateTime {
+    type Error = ShaunError;
+    fn try_from(sn:&'a Shaun) -> Result<&'a DateTime, ShaunError> {
+        sn.as_datetime().ok_or_else(|| ShaunError::mismatch(ShaunErrorKind::NotADateTime, sn))
+    }
+}
+
+/*
+ * Owned values read from borrowed ones clone only what they hold
+ */
+impl<'a> TryFrom<&'a Shaun> for String {
+    type Error = ShaunError;
+    fn try_from(sn:&'a Shaun) -> Result<String, ShaunError> {
+        <&str>::try_from(sn).map(str::to_string)
+    }
+}
+
+impl<'a> TryFrom<&'a Shaun> for Vec<Shaun> {
+    type Error = ShaunError;
+    fn try_from(sn:&'a Shaun) -> Result<Vec<Shaun>, ShaunError> {
+        <&[Shaun]>::try_from(sn).map(<[Shaun]>::to_vec)
+    }
+}
+
+impl<'a> TryFrom<&'a Shaun> for HashMap<String, Shaun> {
+    type Error = ShaunError;
+    fn try_from(sn:&'a Shaun) -> Result<HashMap<String, Shaun>, ShaunError> {
+        <&HashMap<String, Shaun>>::try_from(sn).cloned()
+    }
+}
+
+impl<'a> TryFrom<&'a Shaun> for DateTime {
+    type Error = ShaunError;
+    fn try_from(sn:&'a Shaun) -> Result<DateTime, ShaunError> {
+        <&DateTime>::try_from(sn).cloned()
+    }
+}
+
 impl<'a, T : Clone> From<&'a T> for Shaun where Shaun : From<T> {
     fn from(x:&'a T) -> Self {
         Shaun::from(x.clone())
     }
 }
 
+/*
+ * is_missing() tells whether an error of require() is about a value
+ * which is not there, rather than of another type
+ */
+fn is_missing(kind:&ShaunErrorKind) -> bool {
+    matches!(kind, ShaunErrorKind::AttributeNotFound(_) | ShaunErrorKind::OutOfBound(_))
+}
+
+/*
+ * integer() is the value of an integral number, for conversions to
+ * integer primitives
+ */
+fn integer(n:&Num, target:&str) -> Result<i128, ShaunError> {
+    if let Some(i) = n.as_i128() { return Ok(i) }
+
+    let f = n.as_f64();
+    if f.fract() != 0.0 || f.is_nan() {
+        Err(ShaunError { found: Some(n.to_string()), ..ShaunError::new(ShaunErrorKind::NotAnInteger) })
+    }
+    else if f.abs() >= 2f64.powi(127) { Err(out_of_range(n, target)) }
+    else { Ok(f as i128) }
+}
+
+fn out_of_range(n:&Num, target:&str) -> ShaunError {
+    ShaunError { found: Some(n.to_string()), ..ShaunError::new(ShaunErrorKind::OutOfRange(target.to_string())) }
+}
+
+macro_rules! integer_conversions {
+    ($($t:ty => $variant:ident as $as:ty),*) => {$(
+        impl<'a> TryFrom<&'a Shaun> for $t {
+            type Error = ShaunError;
+            fn try_from(sn:&'a Shaun) -> Result<$t, ShaunError> {
+                match sn {
+                    Shaun::Number(n, _) => {
+                        let i = integer(n, stringify!($t))?;
+                        <$t>::try_from(i).map_err(|_| out_of_range(n, stringify!($t)))
+                    },
+                    _ => Err(ShaunError::mismatch(ShaunErrorKind::NotANumber, sn)),
+                }
+            }
+        }
+
+        impl TryFrom<Shaun> for $t {
+            type Error = ShaunError;
+            fn try_from(sn:Shaun) -> Result<$t, ShaunError> {
+                <$t>::try_from(&sn)
+            }
+        }
+
+        impl From<$t> for Shaun {
+            fn from(x:$t) -> Self {
+                Shaun::Number(Num::$variant(x as $as), String::new())
+            }
+        }
+    )*}
+}
+
+integer_conversions! {
+    i8 => Int as i64, i16 => Int as i64, i32 => Int as i64, i64 => Int as i64, isize => Int as i64,
+    u8 => UInt as u64, u16 => UInt as u64, u32 => UInt as u64, u64 => UInt as u64, usize => UInt as u64
+}
+
 impl From<f64> for Shaun {
     fn from(x:f64) -> Self {
+        Shaun::Number(Num::Float(x), String::new())
+    }
+}
+
+impl From<Num> for Shaun {
+    fn from(x:Num) -> Self {
         Shaun::Number(x, String::new())
     }
 }
 
+impl From<DateTime> for Shaun {
+    fn from(x:DateTime) -> Self {
+        Shaun::DateTime(x)
+    }
+}
+
 impl From<String> for Shaun {
     fn from(x:String) -> Self {
         Shaun::String(x)
@@ -1,12 +1,20 @@
 use shaun_type::Shaun;
+use number::Num;
+use datetime::DateTime;
+use lexer::is_bare_key;
+use writer::{multiline, quoted};
 
 use std::collections::HashMap;
 use std::io::Write;
 
 pub trait Visitor {
     fn visit_null(&mut self);
-    fn visit_number(&mut self, value:&f64, unit:&String);
+    fn visit_number(&mut self, value:&Num, unit:&String);
     fn visit_string(&mut self, value:&String);
+    /// Dates and times are visited as strings, unless told otherwise.
+    fn visit_datetime(&mut self, value:&DateTime) {
+        self.visit_string(&value.to_string())
+    }
     fn visit_bool(&mut self, value:&bool);
     fn visit_list(&mut self, list:&Vec<Shaun>);
     fn visit_object(&mut self, object:&HashMap<String,Shaun>);
@@ -33,14 +41,17 @@ impl<'a, T : Write> PrettyPrinter<'a, T> {
 
 impl<'a, T : Write> Visitor for PrettyPrinter<'a, T> {
   fn visit_null(&mut self) { write!(self.buffer, "null").unwrap() }
-  fn visit_number(&mut self, value:&f64, unit:&String) {
+  fn visit_number(&mut self, value:&Num, unit:&String) {
     write!(self.buffer, "{} {}", value, unit).unwrap()
   }
 
   fn visit_string(&mut self, value:&String) {
-    let value = value.replace("\\", "\\\\");
-    let value = value.replace("\"", "\\\"");
-    write!(self.buffer, "\"{}\"", value).unwrap()
+    let value = multiline(value, self.level).unwrap_or_else(|| quoted(value));
+    write!(self.buffer, "{}", value).unwrap()
+  }
+
+  fn visit_datetime(&mut self, value:&DateTime) {
+    write!(self.buffer, "{}", value).unwrap()
   }
 
   fn visit_bool(&mut self, value:&bool) {
@@ -63,6 +74,8 @@ impl<'a, T : Write> Visitor for PrettyPrinter<'a, T> {
     self.level += 2;
     for (key, sn) in map.iter() {
       self.spaces();
+      let key = if is_bare_key(key) { key.clone() }
+        else { quoted(key) };
       write!(self.buffer, "{}: ", key).unwrap();
       let kl = key.len();
       self.level += kl + 2;
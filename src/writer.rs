@@ -0,0 +1,318 @@
+use shaun_type::Shaun;
+use lexer::is_bare_key;
+use number::Num;
+use datetime::DateTime;
+
+use std::io;
+use std::io::Write;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Container {
+    Object,
+    List,
+}
+
+/// Writes SHAUN data incrementally to any `std::io::Write`.
+///
+/// Objects and lists are opened and closed explicitly, so large datasets
+/// can be exported without building a `Shaun` tree first. Every value is
+/// written on its own line, indented by its depth, unless the writer is
+/// `compact`, in which case everything fits on a single line. I/O errors, as well as
+/// calls which would produce malformed SHAUN (a value without key in an
+/// object, an unbalanced `end_list`, ...), are returned as `io::Error`s.
+///
+/// # Examples
+/// ```
+/// use shaun::Writer;
+///
+/// let mut w = Writer::new(Vec::new());
+/// w.begin_object().unwrap();
+/// w.key("speed").unwrap();
+/// w.number_with_unit(10.0, "km").unwrap();
+/// w.end_object().unwrap();
+///
+/// let out = w.finish().unwrap();
+/// assert_eq!(String::from_utf8(out).unwrap(), "{\n  speed: 10 km\n}\n");
+/// ```
+pub struct Writer<W : Write> {
+    out : W,
+    indent : usize,
+    stack : Vec<(Container, bool)>,
+    after_key : bool,
+    written : bool,
+    rooted : bool,
+    compact : bool,
+}
+
+fn misuse(msg:&str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg)
+}
+
+/*
+ * quoted() writes a string between double quotes, escaping what would not
+ * be read back as is
+ */
+pub fn quoted(s:&str) -> String {
+    let mut ret = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '\\' => ret.push_str("\\\\"),
+            '"' => ret.push_str("\\\""),
+            '\n' => ret.push_str("\\n"),
+            '\t' => ret.push_str("\\t"),
+            '\r' => ret.push_str("\\r"),
+            '\0' => ret.push_str("\\0"),
+            '\u{8}' => ret.push_str("\\b"),
+            '\u{c}' => ret.push_str("\\f"),
+            _ if c.is_control() => ret.push_str(&format!("\\u{:04x}", c as u32)),
+            _ => ret.push(c),
+        }
+    }
+    ret.push('"');
+    ret
+}
+
+/*
+ * Strings of several lines longer than this are written on several lines
+ */
+const MULTILINE_LENGTH : usize = 40;
+
+/*
+ * multiline() writes a long string of several lines with the quotes on
+ * their own lines, every line being indented by `indent` spaces, as the
+ * closing quote, so that the indentation is stripped when read back
+ */
+pub fn multiline(s:&str, indent:usize) -> Option<String> {
+    if !s.contains('\n') || s.len() <= MULTILINE_LENGTH { return None }
+
+    let pad = " ".repeat(indent);
+    let mut ret = String::from("\"\n");
+    for l in s.split('\n') {
+        if !l.is_empty() {
+            let q = quoted(l);
+            ret.push_str(&pad);
+            ret.push_str(&q[1..q.len() - 1]);
+        }
+        ret.push('\n');
+    }
+    ret.push_str(&pad);
+    ret.push('"');
+    Some(ret)
+}
+
+impl<W : Write> Writer<W> {
+    pub fn new(out:W) -> Writer<W> {
+        Writer { out, indent: 2, stack: Vec::new(), after_key: false, written: false, rooted: false, compact: false }
+    }
+
+    /// Creates a writer putting everything on a single line, values being
+    /// separated by commas.
+    pub fn compact(out:W) -> Writer<W> {
+        Writer { compact: true, ..Writer::new(out) }
+    }
+
+    /// Sets the number of spaces added for every level of nesting.
+    pub fn with_indent(mut self, indent:usize) -> Writer<W> {
+        self.indent = indent;
+        self
+    }
+
+    /// Checks every object and list has been closed, then hands back the
+    /// underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        if !self.stack.is_empty() || self.after_key {
+            return Err(misuse("Writer::finish(): unclosed object or list"))
+        }
+        if self.written { writeln!(self.out)?; }
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    fn line(&mut self) -> io::Result<()> {
+        if self.written { writeln!(self.out)?; }
+        self.written = true;
+        write!(self.out, "{}", " ".repeat(self.indent * self.stack.len()))
+    }
+
+    /*
+     * item() starts a new entry of the current object or list (or the
+     * root value when there is none)
+     */
+    fn item(&mut self, first:bool) -> io::Result<()> {
+        if !self.compact { return self.line() }
+        self.written = true;
+        if !first { write!(self.out, ", ")?; }
+        Ok(())
+    }
+
+    /*
+     * prepare_value() places the cursor where the next value goes: right
+     * after its key in objects, on a new line in lists
+     */
+    fn prepare_value(&mut self) -> io::Result<()> {
+        if self.after_key {
+            self.after_key = false;
+            return Ok(())
+        }
+
+        let first = match self.stack.last_mut() {
+            Some(&mut (Container::Object, _)) => return Err(misuse("Writer: value without key in object")),
+            Some(&mut (Container::List, ref mut empty)) => { let first = *empty; *empty = false; first },
+            None => {
+                if self.rooted { return Err(misuse("Writer: only one root value can be written")) }
+                self.rooted = true;
+                !self.written
+            },
+        };
+        self.item(first)
+    }
+
+    fn end(&mut self, container:Container, symbol:&str) -> io::Result<()> {
+        match self.stack.last() {
+            Some(&(c, empty)) if c == container && !self.after_key => {
+                self.stack.pop();
+                if !empty && !self.compact { self.line()?; }
+                write!(self.out, "{}", symbol)
+            },
+            _ => Err(misuse("Writer: unbalanced end of object or list")),
+        }
+    }
+
+    pub fn begin_object(&mut self) -> io::Result<()> {
+        self.prepare_value()?;
+        self.stack.push((Container::Object, true));
+        write!(self.out, "{{")
+    }
+
+    pub fn end_object(&mut self) -> io::Result<()> {
+        self.end(Container::Object, "}")
+    }
+
+    pub fn begin_list(&mut self) -> io::Result<()> {
+        self.prepare_value()?;
+        self.stack.push((Container::List, true));
+        write!(self.out, "[")
+    }
+
+    pub fn end_list(&mut self) -> io::Result<()> {
+        self.end(Container::List, "]")
+    }
+
+    pub fn key(&mut self, key:&str) -> io::Result<()> {
+        let first = match self.stack.last_mut() {
+            Some(&mut (Container::Object, ref mut empty)) if !self.after_key => { let first = *empty; *empty = false; first },
+            _ => return Err(misuse("Writer: key outside of an object")),
+        };
+        self.item(first)?;
+        self.after_key = true;
+        if is_bare_key(key) { write!(self.out, "{}: ", key) }
+        else { write!(self.out, "{}: ", quoted(key)) }
+    }
+
+    pub fn null(&mut self) -> io::Result<()> {
+        self.prepare_value()?;
+        write!(self.out, "null")
+    }
+
+    pub fn bool(&mut self, value:bool) -> io::Result<()> {
+        self.prepare_value()?;
+        write!(self.out, "{}", value)
+    }
+
+    pub fn number<N : Into<Num>>(&mut self, value:N) -> io::Result<()> {
+        self.number_with_unit(value, "")
+    }
+
+    pub fn number_with_unit<N : Into<Num>>(&mut self, value:N, unit:&str) -> io::Result<()> {
+        self.write_number(&value.into(), unit)
+    }
+
+    fn write_number(&mut self, value:&Num, unit:&str) -> io::Result<()> {
+        self.prepare_value()?;
+        if unit.is_empty() { write!(self.out, "{}", value) }
+        else { write!(self.out, "{} {}", value, unit) }
+    }
+
+    pub fn datetime(&mut self, value:&DateTime) -> io::Result<()> {
+        self.prepare_value()?;
+        write!(self.out, "{}", value)
+    }
+
+    /// Writes a string between quotes, on several lines when it is long
+    /// and made of several lines, unless the writer is `compact`.
+    pub fn string(&mut self, value:&str) -> io::Result<()> {
+        self.prepare_value()?;
+        let indent = self.indent * (self.stack.len() + 1);
+        match multiline(value, indent) {
+            Some(m) if !self.compact => write!(self.out, "{}", m),
+            _ => write!(self.out, "{}", quoted(value)),
+        }
+    }
+
+    /// Names the value written next, so that it can be referred to with
+    /// `alias`.
+    pub fn anchor(&mut self, name:&str) -> io::Result<()> {
+        self.prepare_value()?;
+        // the anchored value follows, as it would follow a key
+        self.after_key = true;
+        write!(self.out, "&{} ", name)
+    }
+
+    /// Writes a reference to a value named with `anchor`.
+    pub fn alias(&mut self, name:&str) -> io::Result<()> {
+        self.prepare_value()?;
+        write!(self.out, "*{}", name)
+    }
+
+    /// Writes an expression, computing the value once read back.
+    pub fn expression(&mut self, text:&str) -> io::Result<()> {
+        self.prepare_value()?;
+        write!(self.out, "$({})", text)
+    }
+
+    /// Writes a comment on its own line(s), or as a `/* */` block when
+    /// compact. Comments cannot be placed between a key and its value.
+    pub fn comment(&mut self, text:&str) -> io::Result<()> {
+        if self.after_key {
+            return Err(misuse("Writer: comment between a key and its value"))
+        }
+        if self.compact {
+            if text.contains('\n') || text.contains("*/") {
+                return Err(misuse("Writer: comment does not fit on a single line"))
+            }
+            if self.written { write!(self.out, " ")?; }
+            self.written = true;
+            return write!(self.out, "/* {} */ ", text)
+        }
+        if let Some(top) = self.stack.last_mut() { top.1 = false }
+        for l in text.lines() {
+            self.line()?;
+            write!(self.out, "# {}", l)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a whole Shaun value.
+    pub fn value(&mut self, value:&Shaun) -> io::Result<()> {
+        match *value {
+            Shaun::Null => self.null(),
+            Shaun::Number(ref v, ref u) => self.write_number(v, u),
+            Shaun::DateTime(ref d) => self.datetime(d),
+            Shaun::String(ref s) => self.string(s),
+            Shaun::Bool(b) => self.bool(b),
+            Shaun::List(ref l) => {
+                self.begin_list()?;
+                for sn in l.iter() { self.value(sn)? }
+                self.end_list()
+            },
+            Shaun::Object(ref o) => {
+                self.begin_object()?;
+                for (k, sn) in o.iter() {
+                    self.key(k)?;
+                    self.value(sn)?;
+                }
+                self.end_object()
+            },
+        }
+    }
+}
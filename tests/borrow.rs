@@ -0,0 +1,42 @@
+extern crate shaun;
+
+use shaun::{Num, Shaun, ShaunErrorKind};
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+#[test]
+fn as_accessors() {
+    let sn = shaun::parse_str("rate: 2.5 kg name: \"app\" on: false list: [1 2] day: 2026-10-18");
+    let rate = sn.get("rate").unwrap();
+    assert_eq!(rate.as_f64(), Some(2.5));
+    assert_eq!(rate.unit(), Some("kg"));
+    assert_eq!(rate.as_number(), Some((2.5, "kg")));
+    assert_eq!(rate.as_str(), None);
+    assert_eq!(sn.get("name").unwrap().as_str(), Some("app"));
+    assert_eq!(sn.get("name").unwrap().unit(), None);
+    assert_eq!(sn.get("on").unwrap().as_bool(), Some(false));
+    assert_eq!(sn.get("list").unwrap().as_list().map(|l| l.len()), Some(2));
+    assert_eq!(sn.as_object().map(|o| o.len()), Some(5));
+    assert_eq!(Shaun::from(3).unit(), Some(""));
+}
+
+#[test]
+fn try_from_borrowed() {
+    let sn = shaun::parse_str("rate: 2.5 name: \"app\" on: true list: [1 2] port: 70000 day: 2026-10-18");
+    assert_eq!(<&str>::try_from(sn.get("name").unwrap()), Ok("app"));
+    assert_eq!(f64::try_from(sn.get("rate").unwrap()), Ok(2.5));
+    assert_eq!(bool::try_from(sn.get("on").unwrap()), Ok(true));
+    assert_eq!(<&[Shaun]>::try_from(sn.get("list").unwrap()).unwrap(), &[Shaun::from(1), Shaun::from(2)]);
+    assert_eq!(<&HashMap<String, Shaun>>::try_from(&sn).unwrap().len(), 6);
+    assert_eq!(<&Num>::try_from(sn.get("port").unwrap()), Ok(&Num::UInt(70000)));
+    assert_eq!(u32::try_from(sn.get("port").unwrap()), Ok(70000));
+    assert!(<&shaun::DateTime>::try_from(sn.get("day").unwrap()).is_ok());
+
+    let err = u16::try_from(sn.get("port").unwrap()).unwrap_err();
+    assert_eq!(err.kind(), &ShaunErrorKind::OutOfRange("u16".to_string()));
+    let err = <&str>::try_from(sn.get("list").unwrap()).unwrap_err();
+    assert_eq!(err.found(), Some("a list"));
+    let err = <&shaun::DateTime>::try_from(sn.get("name").unwrap()).unwrap_err();
+    assert_eq!(err.to_string(), "expected a date, found a string");
+}
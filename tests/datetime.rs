@@ -0,0 +1,90 @@
+extern crate shaun;
+#[macro_use]
+extern crate serde_derive;
+
+use shaun::{Date, DateTime, ErrorKind, Shaun, Writer};
+use std::time::Duration;
+
+#[test]
+fn read_dates_and_times() {
+    let sn = shaun::parse_str("
+        release: 2026-10-18T12:00:00Z
+        local: 2026-10-18T09:30:00.25
+        paris: 2026-10-18T14:00:00+02:00
+        day: 2026-10-18
+        opening: 09:30
+    ");
+
+    let release = sn.get("release").unwrap().as_datetime().unwrap();
+    assert_eq!(release.date(), Some(Date { year: 2026, month: 10, day: 18 }));
+    assert_eq!(release.offset(), Some(0));
+    assert_eq!(release.unix_timestamp(), Some(1_792_324_800));
+    assert_eq!(sn.get("paris").unwrap().as_datetime().unwrap().unix_timestamp(), Some(1_792_324_800));
+
+    let local = sn.get("local").unwrap().as_datetime().unwrap();
+    assert_eq!(local.time().unwrap().nanosecond, 250_000_000);
+    assert_eq!(local.unix_timestamp(), None);
+
+    assert_eq!(sn.get("day").unwrap(), &Shaun::from(DateTime::parse("2026-10-18").unwrap()));
+    let opening = sn.get("opening").unwrap().as_datetime().unwrap();
+    assert_eq!((opening.date(), opening.time().unwrap().hour), (None, 9));
+}
+
+#[test]
+fn invalid_dates() {
+    for input in &["a: 2026-02-30", "a: 2026-13-01", "a: 24:00", "a: 2026-10-18T12:00+2"] {
+        let err = shaun::parse_reader(input.as_bytes()).unwrap_err();
+        match err.kind() {
+            ErrorKind::InvalidDateTime(_) => (),
+            k => panic!("unexpected error kind {:?} for {}", k, input),
+        }
+    }
+}
+
+#[test]
+fn durations() {
+    let sn = shaun::parse_str("timeout: 1h 30min retry: 500 ms steps: [1 s 2 s] speed: 10 km");
+
+    assert_eq!(sn.get("timeout").unwrap(), &Shaun::Number(90.0.into(), "min".to_string()));
+    assert_eq!(sn.get("timeout").unwrap().as_duration(), Some(Duration::from_secs(5400)));
+    assert_eq!(sn.get("retry").unwrap().as_duration(), Some(Duration::from_millis(500)));
+    assert_eq!(sn.get("steps").unwrap().to_vec().unwrap().len(), 2);
+    assert_eq!(sn.get("speed").unwrap().as_duration(), None);
+
+    assert!(shaun::parse_reader("a: 1 h 2 km".as_bytes()).is_err());
+
+    // compound durations read the same in lists, unless separated by commas
+    let sn = shaun::parse_str("a: [1h 30min] b: [1h, 30min] c: [30min 1h]");
+    assert_eq!(sn.get("a").unwrap(), &Shaun::from(vec![Shaun::Number(90.0.into(), "min".to_string())]));
+    assert_eq!(sn.get("b").unwrap().to_vec().unwrap().len(), 2);
+    assert_eq!(sn.get("c").unwrap().to_vec().unwrap().len(), 2);
+}
+
+#[test]
+fn numbers_before_dashes_and_colons() {
+    let sn = shaun::parse_str("a: [2026-1 2026 -10-18] b: [10 -5]");
+    assert_eq!(sn.get("a").unwrap(), &Shaun::from(vec![2026.0, -1.0, 2026.0, -10.0, -18.0]));
+    assert_eq!(sn.get("b").unwrap(), &Shaun::from(vec![10.0, -5.0]));
+    assert!(shaun::parse_str("a: 12:30").get("a").unwrap().as_datetime().is_some());
+}
+
+#[test]
+fn write_dates() {
+    let sn = shaun::parse_str("at: 2026-10-18T12:00:00.5-05:30");
+    let mut w = Writer::compact(Vec::new());
+    w.value(&sn).unwrap();
+    let written = String::from_utf8(w.finish().unwrap()).unwrap();
+    assert!(written.contains("at: 2026-10-18T12:00:00.5-05:30"));
+    assert_eq!(shaun::parse_str(&written), sn);
+}
+
+#[test]
+fn deserialize_dates_and_durations() {
+    #[derive(Deserialize)]
+    struct Job { start: String, every: Duration, at: DateTime }
+
+    let job : Job = shaun::from_reader("start: 2026-10-18 every: 1d 12h at: 08:00:00".as_bytes()).unwrap();
+    assert_eq!(job.start, "2026-10-18");
+    assert_eq!(job.every, Duration::from_secs(129_600));
+    assert_eq!(job.at, DateTime::parse("08:00").unwrap());
+}
@@ -0,0 +1,39 @@
+extern crate shaun;
+#[macro_use]
+extern crate serde_derive;
+
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq, Deserialize)]
+enum Mode { Fast, Slow }
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Config {
+    name : String,
+    port : u16,
+    ratio : f32,
+    mode : Mode,
+    tags : Vec<String>,
+    limits : HashMap<String, i64>,
+    backup : Option<String>,
+}
+
+#[test]
+fn from_reader_struct() {
+    let input = "name: \"svc\" port: 8080 ratio: 0.5 mode: \"Slow\"
+                 tags: [\"a\" \"b\"] limits: { low: -2 high: 10 } backup: null";
+    let config : Config = shaun::from_reader(input.as_bytes()).unwrap();
+    assert_eq!(config.name, "svc");
+    assert_eq!(config.port, 8080);
+    assert_eq!(config.ratio, 0.5);
+    assert_eq!(config.mode, Mode::Slow);
+    assert_eq!(config.tags, vec!["a".to_string(), "b".to_string()]);
+    assert_eq!(config.limits["low"], -2);
+    assert_eq!(config.backup, None);
+}
+
+#[test]
+fn from_reader_type_mismatch() {
+    let ret : Result<Config, shaun::ParseError> = shaun::from_reader("name: 10".as_bytes());
+    assert!(ret.is_err())
+}
@@ -0,0 +1,51 @@
+#![cfg(feature = "decimal")]
+extern crate shaun;
+
+use shaun::{Decimal, Num, Shaun, Writer};
+use std::convert::TryFrom;
+
+#[test]
+fn keep_exact_decimals() {
+    let sn = shaun::parse_str("a: 0.1 b: 3.14159265358979323846264338327950288 c: 1.50 km d: 42");
+
+    match *sn.get("b").unwrap() {
+        Shaun::Number(Num::Decimal(ref d), _) => assert_eq!(d.to_string(), "3.14159265358979323846264338327950288"),
+        ref n => panic!("unexpected number {:?}", n),
+    }
+    assert_eq!(sn.get("a").unwrap(), &Shaun::from(Num::Decimal(Decimal::parse("0.10").unwrap())));
+    assert_eq!(sn.get("d").unwrap(), &Shaun::from(42));
+    assert_eq!(sn.get("c").unwrap(), &Shaun::Number(1.5.into(), "km".to_string()));
+
+    let mut w = Writer::compact(Vec::new());
+    w.value(&sn).unwrap();
+    let written = String::from_utf8(w.finish().unwrap()).unwrap();
+    assert!(written.contains("3.14159265358979323846264338327950288"));
+    assert!(written.contains("1.50 km"));
+}
+
+#[test]
+fn compare_and_convert_decimals() {
+    let d = |s| Num::Decimal(Decimal::parse(s).unwrap());
+
+    assert_eq!(d("1e3"), Num::UInt(1000));
+    assert!(d("-0.5") < d("0"));
+    assert!(d("12.5") > d("9.99"));
+    assert!(d("-12.5") < d("-9.99"));
+    assert_eq!(d("2.5e1").as_i64(), Some(25));
+    assert_eq!(d("0.25").as_f64(), 0.25);
+    assert_eq!(i32::try_from(Shaun::from(d("1_000.0"))), Ok(1000));
+}
+
+#[test]
+fn extreme_exponents() {
+    assert_eq!(Decimal::parse("1.5e-9223372036854775808"), None);
+    assert_eq!(Decimal::parse("10e9223372036854775807"), None);
+    match *shaun::parse_reader("a: 1.5e-9223372036854775808".as_bytes()).unwrap_err().kind() {
+        shaun::ErrorKind::InvalidNumber(_) => (),
+        ref k => panic!("unexpected error kind {:?}", k),
+    }
+
+    let big = Num::Decimal(Decimal::parse("1e9223372036854775807").unwrap());
+    assert!(big > Num::Decimal(Decimal::parse("9e9223372036854775806").unwrap()));
+    assert_eq!(big.as_i64(), None);
+}
@@ -0,0 +1,62 @@
+extern crate shaun;
+
+use shaun::{parse_with_options, Diagnostic, ParseOptions, Position, Reader};
+
+fn first_error(s:&str) -> Diagnostic {
+    let err = Reader::new(s.as_bytes()).find_map(|e| e.err()).unwrap();
+    Diagnostic::from(&err)
+}
+
+#[test]
+fn render_number_error() {
+    let source = "a: 1\nb: {\n  c: 0x\n}\n";
+    let d = first_error(source).in_file("config.sn");
+    assert_eq!(d.render(source), "\
+error: invalid number `0x`
+ --> config.sn:3:6
+  |
+3 |   c: 0x
+  |      ^^
+  |
+  = help: numbers are written as `42`, `-1.5`, `6.02e23` or `0xFF`
+");
+}
+
+#[test]
+fn suggest_keywords() {
+    assert_eq!(first_error("a: true flase").help(), Some("did you mean `false`?"));
+    assert_eq!(first_error("a: [1] nul").help(), Some("did you mean `null`?"));
+    assert_eq!(first_error("a: [1] port").help(), Some("a key is followed by `:`, as in `port: value`"));
+}
+
+#[test]
+fn render_without_source_line() {
+    let d = Diagnostic::new("missing key `port`").with_help("add `port: 80`");
+    assert_eq!(d.render(""), "error: missing key `port`\n = help: add `port: 80`\n");
+    assert_eq!(d.to_string(), "error: missing key `port`");
+
+    let d = Diagnostic::new("bad value").at(Position { line: 12, column: 3 }, 2);
+    assert_eq!(d.to_string(), "error: bad value at line 12, column 3");
+    assert_eq!(d.render("x"), "error: bad value\n  --> <input>:12:3\n");
+
+    let d = Diagnostic::new("bad value").at(Position { line: 0, column: 0 }, 1);
+    assert_eq!(d.render("x"), "error: bad value\n --> <input>:0:0\n");
+    let d = Diagnostic::new("bad value").at(Position { line: 1, column: 0 }, 1);
+    assert_eq!(d.render("x"), "error: bad value\n --> <input>:1:0\n  |\n1 | x\n  | ^\n");
+}
+
+#[test]
+fn render_colored() {
+    let d = Diagnostic::new("bad value").at(Position { line: 1, column: 4 }, 1).colored(true);
+    let rendered = d.render("a: !");
+    assert!(rendered.starts_with("\x1b[1;31merror\x1b[0m"));
+    assert!(rendered.contains("\x1b[1;31m^\x1b[0m"));
+}
+
+#[test]
+fn render_parse_errors() {
+    let err = parse_with_options("a: { b: 1".as_bytes(), &ParseOptions::default()).unwrap_err();
+    let d = Diagnostic::from(&err);
+    assert!(d.help().unwrap().contains("closing"));
+    assert!(d.render("a: { b: 1").starts_with("error: unexpected end of input"));
+}
@@ -0,0 +1,39 @@
+extern crate shaun;
+
+use shaun::{Shaun, ShaunErrorKind};
+
+use std::convert::TryFrom;
+use std::error::Error;
+
+fn port(sn:&Shaun) -> Result<u16, Box<dyn Error>> {
+    let server = sn.get("server")?;
+    Ok(u16::try_from(server.get("port")?.clone()).map_err(|e| e.at_path("server.port"))?)
+}
+
+#[test]
+fn errors_carry_context() {
+    let sn = shaun::parse_str("list: [1 2] server: { port: 70000 } name: \"x\"");
+
+    let err = sn.get("missing").unwrap_err();
+    assert_eq!(err.kind(), &ShaunErrorKind::AttributeNotFound("missing".to_string()));
+    assert_eq!(err.to_string(), "key `missing` not found");
+
+    let err = sn.get("list").unwrap().at(5usize).unwrap_err();
+    assert_eq!(err.kind(), &ShaunErrorKind::OutOfBound(5));
+
+    let err = sn.get("name").unwrap().to_vec().unwrap_err().at_path("name");
+    assert_eq!(err.found(), Some("a string"));
+    assert_eq!(err.path(), "name");
+    assert_eq!(err.to_string(), "expected a list, found a string at `name`");
+}
+
+#[test]
+fn errors_compose_with_question_mark() {
+    let err = port(&shaun::parse_str("server: { port: 70000 }")).unwrap_err();
+    assert_eq!(err.to_string(), "70000 is out of the range of u16 at `server.port`");
+
+    let err = port(&shaun::parse_str("server: 1")).unwrap_err();
+    assert_eq!(err.to_string(), "expected an object, found a number");
+
+    assert_eq!(port(&shaun::parse_str("server: { port: 80 }")).unwrap(), 80);
+}
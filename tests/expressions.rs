@@ -0,0 +1,93 @@
+extern crate shaun;
+
+use shaun::{evaluate, parse_unresolved, ErrorKind, Shaun, Writer};
+
+#[test]
+fn evaluate_expressions() {
+    let sn = shaun::parse_str("
+        base_timeout: 15 s
+        workers: 4
+        prefix: \"api\"
+        timeout: $(2 * base_timeout)
+        buffer: $(4 KiB * workers)
+        name: $(prefix + \"-svc\")
+        server: {
+            workers: 2
+            threads: $(max(workers, 3) * limits.factor)
+            mode: $(if(len(name) > 5, \"long\", \"short\"))
+        }
+        limits: { factor: $(workers / 2) }
+    ");
+
+    assert_eq!(sn.get("timeout").unwrap(), &Shaun::Number(30.0.into(), "s".to_string()));
+    assert_eq!(sn.get("buffer").unwrap(), &Shaun::Number(16.0.into(), "KiB".to_string()));
+    assert_eq!(sn.get("name").unwrap(), &Shaun::from("api-svc"));
+    let server = sn.get("server").unwrap();
+    assert_eq!(server.get("threads").unwrap(), &Shaun::from(6.0));
+    assert_eq!(server.get("mode").unwrap(), &Shaun::from("long"));
+}
+
+#[test]
+fn expression_errors() {
+    let err = shaun::parse_reader("a: 1 s\nb: $(a + 2 m)".as_bytes()).unwrap_err();
+    match err.kind() {
+        ErrorKind::InvalidExpression(m) => assert!(m.contains("units")),
+        k => panic!("unexpected error kind {:?}", k),
+    }
+    assert_eq!(err.position().unwrap().line, 2);
+
+    let err = shaun::parse_reader("a: $(b) b: $(a + 1)".as_bytes()).unwrap_err();
+    match err.kind() {
+        ErrorKind::CyclicExpression(_) => (),
+        k => panic!("unexpected error kind {:?}", k),
+    }
+
+    assert!(shaun::parse_reader("a: $(nope * 2)".as_bytes()).is_err());
+    assert!(shaun::parse_reader("a: $(1 +)".as_bytes()).is_err());
+}
+
+#[test]
+fn long_and_deep_expressions() {
+    let sum = vec!["1"; 8000].join(" + ");
+    let sn = shaun::parse_reader(format!("a: $({})", sum).as_bytes()).unwrap();
+    assert_eq!(sn.get("a").unwrap(), &Shaun::from(8000));
+
+    let chain = vec!["true"; 8000].join(" && ");
+    assert_eq!(evaluate(&chain, &Shaun::Null).unwrap(), Shaun::Bool(true));
+
+    // nesting is limited even without options
+    for input in &[format!("a: $({}1)", "-".repeat(8000)), format!("a: $({}1{})", "(".repeat(8000), ")".repeat(8000))] {
+        let err = shaun::parse_reader(input.as_bytes()).unwrap_err();
+        match err.kind() {
+            ErrorKind::LimitExceeded(_, 64) => (),
+            k => panic!("unexpected error kind {:?}", k),
+        }
+    }
+}
+
+
+#[test]
+fn expressions_through_aliases() {
+    let sn = shaun::parse_str("defaults: &d { port: 80 } web: *d next: $(web.port + 1)");
+    assert_eq!(sn.get("next").unwrap(), &Shaun::from(81.0));
+}
+
+#[test]
+fn write_and_evaluate() {
+    let doc = parse_unresolved("n: 2 m: $(n * (n + 1))".as_bytes()).unwrap();
+    assert!(!doc.is_resolved());
+
+    let mut w = Writer::compact(Vec::new());
+    doc.write(&mut w).unwrap();
+    let written = String::from_utf8(w.finish().unwrap()).unwrap();
+    assert!(written.contains("$(n * (n + 1))"));
+
+    let sn = shaun::parse_str("x: 3");
+    assert_eq!(evaluate("[x, x * 2] + [min(1, x)]", &sn).unwrap(), Shaun::from(vec![3.0, 6.0, 1.0]));
+
+    // errors of expressions evaluated on parsed values have no position
+    for text in &["x +", "y * 2", "-\"a\""] {
+        let err = evaluate(text, &sn).unwrap_err();
+        assert_eq!(err.position(), None, "{}", text);
+    }
+}
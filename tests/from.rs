@@ -4,5 +4,35 @@ use shaun::Shaun;
 
 #[test]
 fn from_float() {
-    assert_eq!(Shaun::Number(10.0, std::string::String::new()), Shaun::from(10.0))
+    assert_eq!(Shaun::Number(10.0.into(), std::string::String::new()), Shaun::from(10.0))
+}
+
+#[test]
+fn from_integers() {
+    assert_eq!(Shaun::from(-3i32), Shaun::from(-3.0));
+    assert_eq!(Shaun::from(7usize), Shaun::Number(shaun::Num::UInt(7), std::string::String::new()));
+    assert_eq!(Shaun::from(u64::MAX), Shaun::Number(shaun::Num::UInt(u64::MAX), std::string::String::new()));
+    assert_eq!(Shaun::from(i64::MIN), Shaun::Number(shaun::Num::Int(i64::MIN), std::string::String::new()));
+}
+
+#[test]
+fn try_from_integers() {
+    use std::convert::TryFrom;
+    use shaun::ShaunErrorKind;
+
+    let sn = shaun::parse_str("id: 18446744073709551615 big: 9007199254740993 neg: -5 ratio: 1.5 two: 2.0e0");
+    assert_eq!(u64::try_from(sn.get("id").unwrap().clone()), Ok(u64::MAX));
+    assert_eq!(i64::try_from(sn.get("big").unwrap().clone()), Ok(9007199254740993));
+    assert_eq!(i8::try_from(sn.get("neg").unwrap().clone()), Ok(-5));
+    assert_eq!(u8::try_from(sn.get("neg").unwrap().clone()).map_err(|e| e.into_kind()), Err(ShaunErrorKind::OutOfRange("u8".to_string())));
+    assert_eq!(i64::try_from(sn.get("id").unwrap().clone()).map_err(|e| e.into_kind()), Err(ShaunErrorKind::OutOfRange("i64".to_string())));
+    assert_eq!(u16::try_from(Shaun::from(70000)).map_err(|e| e.into_kind()), Err(ShaunErrorKind::OutOfRange("u16".to_string())));
+    assert_eq!(i32::try_from(sn.get("ratio").unwrap().clone()).map_err(|e| e.into_kind()), Err(ShaunErrorKind::NotAnInteger));
+    assert_eq!(usize::try_from(sn.get("two").unwrap().clone()), Ok(2));
+    assert_eq!(i32::try_from(Shaun::from("1")).map_err(|e| e.into_kind()), Err(ShaunErrorKind::NotANumber));
+
+    let mut w = shaun::Writer::compact(Vec::new());
+    w.value(&sn).unwrap();
+    let out = String::from_utf8(w.finish().unwrap()).unwrap();
+    assert!(out.contains("id: 18446744073709551615"));
 }
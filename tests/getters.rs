@@ -0,0 +1,83 @@
+extern crate shaun;
+
+use shaun::ShaunErrorKind;
+
+use std::time::Duration;
+
+const CONFIG : &str = "
+rate: 0.5
+name: \"app\"
+debug: true
+timeout: 1min 30s
+retry: 3 m
+hosts: [\"a\" \"b\"]
+server: { ports: [80 443] }
+";
+
+#[test]
+fn typed_getters() {
+    let sn = shaun::parse_str(CONFIG);
+    assert_eq!(sn.get_f64("rate").unwrap(), 0.5);
+    assert_eq!(sn.get_str("name").unwrap(), "app");
+    assert!(sn.get_bool("debug").unwrap());
+    assert_eq!(sn.get_duration("timeout").unwrap(), Duration::from_secs(90));
+    assert_eq!(sn.get_list_of::<String>("hosts").unwrap(), vec!["a", "b"]);
+    assert_eq!(sn.get_list_of::<u16>("server.ports").unwrap(), vec![80, 443]);
+    assert_eq!(sn.get_f64("server.ports[1]").unwrap(), 443.0);
+}
+
+#[test]
+fn typed_getters_errors() {
+    let sn = shaun::parse_str(CONFIG);
+
+    let err = sn.get_str("rate").unwrap_err();
+    assert_eq!(err.to_string(), "expected a string, found a number at `rate`");
+
+    let err = sn.get_duration("retry").unwrap_err();
+    assert_eq!(err.kind(), &ShaunErrorKind::NotADuration);
+    assert_eq!(err.to_string(), "expected a duration, found 3 m at `retry`");
+
+    let err = sn.get_bool("server.ports[2]").unwrap_err();
+    assert_eq!(err.to_string(), "index 2 out of bounds at `server.ports[2]`");
+
+    let err = sn.get_list_of::<u8>("server.ports").unwrap_err();
+    assert_eq!(err.to_string(), "443 is out of the range of u8 at `server.ports[1]`");
+
+    let err = sn.get_list_of::<String>("name").unwrap_err();
+    assert_eq!(err.to_string(), "expected a list, found a string at `name`");
+}
+
+#[test]
+fn defaults() {
+    let sn = shaun::parse_str(CONFIG);
+    assert_eq!(sn.get_or("port", 8080).unwrap(), 8080);
+    assert_eq!(sn.get_or("server.ports[5]", 8080).unwrap(), 8080);
+    assert_eq!(sn.get_or("server.ports[0]", 8080).unwrap(), 80);
+    assert!(!sn.get_or("verbose", false).unwrap());
+    assert_eq!(sn.get_or("name.first", 1).unwrap_err().kind(), &ShaunErrorKind::NotAnObject);
+    assert_eq!(sn.get_or("name", 1).unwrap_err().path(), "name");
+}
+
+#[test]
+fn quoted_and_malformed_paths() {
+    let sn = shaun::parse_str("\"server.name\": 1 a: { \"[b]\": [{ c: 2 }] }");
+    assert_eq!(sn.get_f64("[\"server.name\"]").unwrap(), 1.0);
+    assert_eq!(sn.get_f64("a[\"[b]\"][0].c").unwrap(), 2.0);
+    assert_eq!(sn.require("server.name").unwrap_err().to_string(), "key `server` not found at `server`");
+    assert_eq!(sn.require("a[\"[b]\"][1]").unwrap_err().to_string(), "index 1 out of bounds at `a[\"[b]\"][1]`");
+
+    for path in &["a[", "a..b", "a.", ".a", "a[x]", "a]", "a[0", "[\"a]", "a[0]b"] {
+        assert_eq!(sn.require(path).unwrap_err().kind(), &ShaunErrorKind::InvalidPath(path.to_string()), "{}", path);
+    }
+    // a malformed path is an error rather than missing
+    assert!(sn.get_or("a..b", 1).is_err());
+}
+
+#[test]
+fn getters_of_owned_values() {
+    let sn = shaun::parse_str("grid: [[1 2] [3]] at: 2026-10-18 env: { a: \"1\" }");
+    assert_eq!(sn.get_list_of::<Vec<shaun::Shaun>>("grid").unwrap()[1], vec![shaun::Shaun::from(3)]);
+    assert_eq!(sn.get_or("at", shaun::DateTime::parse("2000-01-01").unwrap()).unwrap().to_string(), "2026-10-18");
+    assert_eq!(sn.get_or("env", std::collections::HashMap::new()).unwrap().len(), 1);
+    assert_eq!(sn.get_list_of::<String>("grid").unwrap_err().path(), "grid[0]");
+}
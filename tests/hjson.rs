@@ -0,0 +1,71 @@
+extern crate shaun;
+
+use shaun::{parse_hjson, Shaun};
+
+#[test]
+fn quoteless_strings() {
+    let sn = parse_hjson("
+    {
+      # comments are allowed
+      title: The Title, part 2
+      url: http://example.com/a#b
+      port: 8080,
+      ratio: 2.5  # a number
+      speed: 10 km
+      enabled: true
+      tags: [
+        first
+        second tag
+        3
+      ]
+      inline: [1, 2, 3]
+    }".as_bytes()).unwrap();
+
+    assert_eq!(sn.get("title").unwrap(), &Shaun::from("The Title, part 2"));
+    assert_eq!(sn.get("url").unwrap(), &Shaun::from("http://example.com/a#b"));
+    assert_eq!(sn.get("port").unwrap(), &Shaun::from(8080.0));
+    assert_eq!(sn.get("ratio").unwrap(), &Shaun::from(2.5));
+    assert_eq!(sn.get("speed").unwrap(), &Shaun::from("10 km"));
+    assert_eq!(sn.get("enabled").unwrap(), &Shaun::Bool(true));
+    assert_eq!(sn.get("tags").unwrap(), &Shaun::List(vec![
+        Shaun::from("first"), Shaun::from("second tag"), Shaun::from(3.0)]));
+    assert_eq!(sn.get("inline").unwrap(), &Shaun::from(vec![1.0, 2.0, 3.0]));
+}
+
+#[test]
+fn quoteless_strings_with_reference_chars() {
+    let sn = parse_hjson("{
+      a: *bold* text
+      b: &amp;
+      c: ${HOME}/bin
+      d: $(not an expression)
+      e: [
+        *x
+      ]
+    }".as_bytes()).unwrap();
+    assert_eq!(sn.get("a").unwrap(), &Shaun::from("*bold* text"));
+    assert_eq!(sn.get("b").unwrap(), &Shaun::from("&amp;"));
+    assert_eq!(sn.get("c").unwrap(), &Shaun::from("${HOME}/bin"));
+    assert_eq!(sn.get("d").unwrap(), &Shaun::from("$(not an expression)"));
+    assert_eq!(sn.get("e").unwrap(), &Shaun::List(vec![Shaun::from("*x")]));
+}
+
+#[test]
+fn multiline_strings() {
+    let input = "text:\n  '''\n  first line\n    indented\n  last line\n  '''\nsingle: 'say \"hi\"'\nempty: ''";
+    let sn = parse_hjson(input.as_bytes()).unwrap();
+    assert_eq!(sn.get("text").unwrap(), &Shaun::from("first line\n  indented\nlast line"));
+    assert_eq!(sn.get("single").unwrap(), &Shaun::from("say \"hi\""));
+    assert_eq!(sn.get("empty").unwrap(), &Shaun::from(""));
+
+    let sn = shaun::parse_str("a: '''one line''' b: 'quoted \\' quote'");
+    assert_eq!(sn.get("a").unwrap(), &Shaun::from("one line"));
+    assert_eq!(sn.get("b").unwrap(), &Shaun::from("quoted ' quote"));
+}
+
+#[test]
+fn bare_words_in_shaun() {
+    let sn = shaun::parse_str("mode: fast level: 3");
+    assert_eq!(sn.get("mode").unwrap(), &Shaun::from("fast"));
+    assert_eq!(sn.get("level").unwrap(), &Shaun::from(3.0));
+}
@@ -0,0 +1,34 @@
+extern crate shaun;
+
+use shaun::Shaun;
+
+#[test]
+fn index_reads_null_for_missing() {
+    let sn = shaun::parse_str("server: { ports: [80 443] name: \"a\" }");
+    assert_eq!(sn["server"]["ports"][1], Shaun::from(443));
+    assert_eq!(sn["server"][String::from("name")], Shaun::from("a"));
+    assert_eq!(sn["server"][&String::from("name")], Shaun::from("a"));
+    assert!(sn["server"]["ports"][2].is_null());
+    assert!(sn["client"]["ports"][0].is_null());
+    assert!(sn["server"]["name"]["first"].is_null());
+    assert!(sn["server"][0].is_null());
+}
+
+#[test]
+fn index_mut_creates_on_assignment() {
+    let mut sn = shaun::parse_str("server: { port: 80 }");
+    sn["server"]["port"] = 8080.into();
+    sn["server"]["hosts"][2] = "c".into();
+    sn["client"]["retries"] = 3.into();
+
+    assert_eq!(sn.get_f64("server.port").unwrap(), 8080.0);
+    assert_eq!(sn["server"]["hosts"], Shaun::List(vec![Shaun::Null, Shaun::Null, Shaun::from("c")]));
+    assert_eq!(sn.get_f64("client.retries").unwrap(), 3.0);
+}
+
+#[test]
+#[should_panic(expected = "cannot index a number with `host`")]
+fn index_mut_panics_on_scalars() {
+    let mut sn = shaun::parse_str("port: 80");
+    sn["port"]["host"] = "a".into();
+}
@@ -0,0 +1,58 @@
+extern crate shaun;
+
+use shaun::{ErrorKind, Interpolator, Resolver, Secrets, Shaun};
+use std::collections::HashMap;
+
+fn vars() -> HashMap<String, String> {
+    let mut vars = HashMap::new();
+    vars.insert("HOME".to_string(), "/home/jar".to_string());
+    vars.insert("PORT".to_string(), "9000".to_string());
+    vars
+}
+
+#[test]
+fn interpolate_while_parsing() {
+    let sn = Interpolator::new(vars()).parse("
+        dir: \"${HOME}/data\"
+        port: ${env:PORT}
+        timeout: ${TIMEOUT:-30 s}
+        literal: \"$${HOME}\"
+    ".as_bytes()).unwrap();
+
+    assert_eq!(sn.get("dir").unwrap(), &Shaun::from("/home/jar/data"));
+    assert_eq!(sn.get("port").unwrap(), &Shaun::from(9000.0));
+    assert_eq!(sn.get("timeout").unwrap(), &Shaun::Number(30.0.into(), "s".to_string()));
+    assert_eq!(sn.get("literal").unwrap(), &Shaun::from("${HOME}"));
+}
+
+#[test]
+fn unknown_variables() {
+    let input = "a: \"${NOPE}\" b: ${NOPE}";
+
+    let sn = Interpolator::new(vars()).parse(input.as_bytes()).unwrap();
+    assert_eq!(sn.get("a").unwrap(), &Shaun::from("${NOPE}"));
+    assert_eq!(sn.get("b").unwrap(), &Shaun::from("${NOPE}"));
+
+    let err = Interpolator::new(vars()).strict(true).parse(input.as_bytes()).unwrap_err();
+    match err.kind() {
+        ErrorKind::UnknownVariable(name) => assert_eq!(name, "NOPE"),
+        k => panic!("unexpected error kind {:?}", k),
+    }
+    assert_eq!(err.position().unwrap().column, 4);
+
+    let err = Interpolator::new(vars()).strict(true).expand("${NOPE}").unwrap_err();
+    assert_eq!(err.position(), None);
+    assert_eq!((err.line(), err.column()), (0, 0));
+    assert_eq!(err.to_string(), "unknown variable `NOPE`");
+}
+
+#[test]
+fn interpolate_after_parsing() {
+    let sn = shaun::parse_str("{ paths: [\"${HOME}/a\" \"${secret:KEY}\"] n: 1 }");
+    let secrets = Secrets::from_reader("# keys\nKEY = s3cr3t\n".as_bytes()).unwrap();
+    let resolvers : Vec<Box<dyn Resolver>> = vec![Box::new(secrets), Box::new(vars())];
+
+    let sn = Interpolator::new(resolvers).interpolate(sn).unwrap();
+    assert_eq!(sn.get("paths").unwrap(), &Shaun::from(vec!["/home/jar/a", "s3cr3t"]));
+    assert_eq!(sn.get("n").unwrap(), &Shaun::from(1.0));
+}
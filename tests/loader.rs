@@ -0,0 +1,64 @@
+extern crate shaun;
+
+use shaun::{ErrorKind, Loader, Shaun};
+use std::path::Path;
+
+#[test]
+fn load_includes() {
+    let loader = Loader::new().search_path("resources/include/shared");
+    let (sn, sources) = loader.load_with_sources(Path::new("resources/include/main.sn")).unwrap();
+
+    assert_eq!(sn.get("port").unwrap(), &Shaun::from(8080.0));
+    assert_eq!(sn.get("name").unwrap(), &Shaun::from("api"));
+    assert_eq!(sn.get("db").unwrap().get("pool").unwrap(), &Shaun::from(4.0));
+
+    let span = sources.get("port").unwrap();
+    assert_eq!(span.file, Path::new("resources/include/common/net.sn"));
+    assert_eq!(span.position.line, 2);
+    let span = sources.get("db.url").unwrap();
+    assert_eq!(span.file, Path::new("resources/include/shared/db.sn"));
+    assert_eq!(sources.get("name").unwrap().file, Path::new("resources/include/main.sn"));
+}
+
+#[test]
+fn load_include_not_found() {
+    let err = Loader::new().load(Path::new("resources/include/main.sn")).unwrap_err();
+    match err.kind() {
+        ErrorKind::IncludeNotFound(name) => assert_eq!(name, "db.sn"),
+        k => panic!("unexpected error kind {:?}", k),
+    }
+    assert_eq!(err.file(), Some(Path::new("resources/include/main.sn")));
+    assert_eq!(err.position().unwrap().line, 5);
+}
+
+#[test]
+fn load_include_cycle() {
+    let err = Loader::new().load(Path::new("resources/include/cycle_a.sn")).unwrap_err();
+    match err.kind() {
+        &ErrorKind::IncludeCycle(_) => (),
+        k => panic!("unexpected error kind {:?}", k),
+    }
+    assert_eq!(err.file(), Some(Path::new("resources/include/cycle_b.sn")));
+}
+
+#[test]
+fn load_include_depth() {
+    let loader = Loader::new().search_path("resources/include/shared").max_depth(0);
+    let err = loader.load(Path::new("resources/include/main.sn")).unwrap_err();
+    match err.kind() {
+        &ErrorKind::IncludeDepth(0) => (),
+        k => panic!("unexpected error kind {:?}", k),
+    }
+}
+
+#[test]
+fn parse_include_without_loader() {
+    assert!(shaun::parse_reader("@include \"net.sn\"".as_bytes()).is_err())
+}
+
+#[test]
+fn bare_include_is_a_string() {
+    let doc = shaun::parse_str("a: include\ninclude: 1");
+    assert_eq!(doc["a"].as_str(), Some("include"));
+    assert_eq!(doc["include"].as_f64(), Some(1.0));
+}
@@ -0,0 +1,157 @@
+extern crate shaun;
+
+use shaun::{parse_with_options, Diagnostic, DuplicateKeys, ErrorKind, Event, Limit, ParseOptions, Reader, Shaun};
+
+fn with_duplicates(duplicate_keys:DuplicateKeys) -> ParseOptions {
+    ParseOptions { duplicate_keys, ..ParseOptions::default() }
+}
+
+#[test]
+fn strict_mode() {
+    let input = "port: 80; host: \"a\"";
+    assert_eq!(shaun::parse_str(input).get("port").unwrap(), &Shaun::from(80.0));
+
+    let strict = ParseOptions { strict: true, ..ParseOptions::default() };
+    let err = parse_with_options(input.as_bytes(), &strict).unwrap_err();
+    match err.kind() {
+        ErrorKind::UnexpectedToken(t) => assert_eq!(t, "character `;`"),
+        k => panic!("unexpected error kind {:?}", k),
+    }
+    assert_eq!(err.position().unwrap().column, 9);
+
+    for input in &["a: 1 @", "a: & 1", "a: $ 1", "a: \"\\q\""] {
+        assert!(parse_with_options(input.as_bytes(), &strict).is_err(), "{}", input);
+    }
+    assert!(parse_with_options("{ a: [1, 2], b: 3 } # ok".as_bytes(), &strict).is_ok());
+
+    // typos of keywords are not read as strings
+    let err = parse_with_options("enabled: ture".as_bytes(), &strict).unwrap_err();
+    match err.kind() {
+        ErrorKind::UnexpectedToken(t) => assert_eq!(t, "identifier `ture`"),
+        k => panic!("unexpected error kind {:?}", k),
+    }
+    assert_eq!(Diagnostic::from(&err).help(), Some("did you mean `true`?"));
+    assert!(parse_with_options("a: [nul]".as_bytes(), &strict).is_err());
+    assert_eq!(parse_with_options("a: label".as_bytes(), &strict).unwrap().get("a").unwrap(), &Shaun::from("label"));
+    assert_eq!(shaun::parse_str("enabled: ture").get("enabled").unwrap(), &Shaun::from("ture"));
+
+    // includes are read in strict mode too, the reader leaving them to a loader
+    for input in &["@include \"net.sn\"", "net: @include \"net.sn\""] {
+        let events : Result<Vec<_>, _> = Reader::with_options(input.as_bytes(), strict.clone()).collect();
+        assert!(events.unwrap().contains(&Event::Include("net.sn".to_string())), "{}", input);
+    }
+}
+
+#[test]
+fn commas() {
+    let options = ParseOptions { allow_commas: false, ..ParseOptions::default() };
+    assert!(parse_with_options("a: [1 2] b: 3".as_bytes(), &options).is_ok());
+    let err = parse_with_options("a: [1, 2]".as_bytes(), &options).unwrap_err();
+    assert_eq!(err.position().unwrap().column, 6);
+}
+
+#[test]
+fn duplicate_keys() {
+    let input = "a: 1 b: { c: 1 c: [2] } a: 2 a: 3";
+
+    let sn = parse_with_options(input.as_bytes(), &ParseOptions::default()).unwrap();
+    assert_eq!(sn.get("a").unwrap(), &Shaun::from(3.0));
+
+    let sn = parse_with_options(input.as_bytes(), &with_duplicates(DuplicateKeys::FirstWins)).unwrap();
+    assert_eq!(sn.get("a").unwrap(), &Shaun::from(1.0));
+    assert_eq!(sn.get("b").unwrap().get("c").unwrap(), &Shaun::from(1.0));
+
+    let sn = parse_with_options(input.as_bytes(), &with_duplicates(DuplicateKeys::Collect)).unwrap();
+    assert_eq!(sn.get("a").unwrap(), &Shaun::from(vec![1.0, 2.0, 3.0]));
+    assert_eq!(sn.get("b").unwrap().get("c").unwrap(), &Shaun::List(vec![Shaun::from(1.0), Shaun::from(vec![2.0])]));
+
+    // keys are collected per object, not per path
+    let sn = parse_with_options("x: {a:1 a:2} x: {a:[1] a:2}".as_bytes(), &with_duplicates(DuplicateKeys::Collect)).unwrap();
+    let x = sn.get("x").unwrap();
+    assert_eq!(x.at(0usize).unwrap().get("a").unwrap(), &Shaun::from(vec![1, 2]));
+    assert_eq!(x.at(1usize).unwrap().get("a").unwrap(), &Shaun::List(vec![Shaun::from(vec![1]), Shaun::from(2)]));
+
+    let err = parse_with_options(input.as_bytes(), &with_duplicates(DuplicateKeys::Error)).unwrap_err();
+    match err.kind() {
+        ErrorKind::DuplicateKey(k) => assert_eq!(k, "c"),
+        k => panic!("unexpected error kind {:?}", k),
+    }
+    assert_eq!(err.position().unwrap().column, 16);
+}
+
+fn limit_exceeded(input:&str, options:&ParseOptions) -> (Limit, u64, u32) {
+    let err = parse_with_options(input.as_bytes(), options).unwrap_err();
+    match *err.kind() {
+        ErrorKind::LimitExceeded(l, n) => (l, n, err.position().unwrap().column),
+        ref k => panic!("unexpected error kind {:?}", k),
+    }
+}
+
+#[test]
+fn limits() {
+    let options = ParseOptions {
+        max_depth: Some(3),
+        max_input_size: Some(100),
+        max_string_length: Some(10),
+        max_keys: Some(3),
+        max_list_length: Some(4),
+        ..ParseOptions::default()
+    };
+    assert!(parse_with_options("a: { b: [1 2 3 4] } c: \"0123456\" d: 1".as_bytes(), &options).is_ok());
+
+    assert_eq!(limit_exceeded("a: { b: [[1]] }", &options), (Limit::Depth, 3, 10));
+    assert_eq!(limit_exceeded(&format!("a: {}", "[".repeat(100_000)), &options), (Limit::Depth, 3, 6));
+    assert_eq!(limit_exceeded(&format!("a: 1{}", " ".repeat(100)), &options).0, Limit::InputSize);
+    assert_eq!(limit_exceeded("a: 1 b: \"a long string\"", &options), (Limit::StringLength, 10, 9));
+    assert_eq!(limit_exceeded("# a long comment\na: 1", &options), (Limit::StringLength, 10, 1));
+    assert_eq!(limit_exceeded("a: 1 abcdefghijk: 2", &options), (Limit::StringLength, 10, 6));
+    assert_eq!(limit_exceeded("a: 1 b: 2 c: 3 d: 4", &options), (Limit::Keys, 3, 16));
+    assert_eq!(limit_exceeded("a: [1 [2] 3 { b: 4 } 5]", &options), (Limit::ListLength, 4, 22));
+}
+
+#[test]
+fn string_length_at_the_limit() {
+    let options = ParseOptions { max_string_length: Some(3), ..ParseOptions::default() };
+    for input in &["abc: 1", "abc : 1", "a: abc", "a: abc\n", "\"a\": 1", "a: [abc]"] {
+        assert!(parse_with_options(input.as_bytes(), &options).is_ok(), "{}", input);
+    }
+    assert_eq!(limit_exceeded("abcd: 1", &options), (Limit::StringLength, 3, 1));
+    assert_eq!(limit_exceeded("a: abcd", &options), (Limit::StringLength, 3, 4));
+}
+
+#[test]
+fn expression_depth() {
+    let options = ParseOptions { max_depth: Some(32), ..ParseOptions::default() };
+    let input = format!("a: $({}1)", "-".repeat(8000));
+    assert_eq!(limit_exceeded(&input, &options), (Limit::Depth, 32, 4));
+    let input = format!("a: $({}1{})", "(".repeat(40), ")".repeat(40));
+    assert_eq!(limit_exceeded(&input, &options), (Limit::Depth, 32, 4));
+
+    let input = format!("a: $({}1{})", "(".repeat(20), ")".repeat(20));
+    assert_eq!(parse_with_options(input.as_bytes(), &options).unwrap().get("a").unwrap(), &Shaun::from(1));
+}
+
+
+#[test]
+fn alias_expansion() {
+    let mut input = "a: &a [1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1]\n".to_string();
+    for (name, alias) in ["b", "c", "d", "e", "f"].iter().zip(["a", "b", "c", "d", "e"].iter()) {
+        input.push_str(&format!("{}: &{} [{}]\n", name, name, vec![format!("*{}", alias); 16].join(" ")));
+    }
+    let options = ParseOptions {
+        max_depth: Some(8),
+        max_input_size: Some(10_000),
+        max_string_length: Some(64),
+        max_keys: Some(16),
+        max_list_length: Some(16),
+        ..ParseOptions::default()
+    };
+    assert_eq!(limit_exceeded(&input, &options).0, Limit::Values);
+
+    // the values copied by aliases count, as do those they replace
+    let input = "a: &a [1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1] b: [*a *a]";
+    let options = ParseOptions { max_values: Some(53), ..ParseOptions::default() };
+    assert!(parse_with_options(input.as_bytes(), &options).is_ok());
+    let options = ParseOptions { max_values: Some(52), ..ParseOptions::default() };
+    assert_eq!(limit_exceeded(input, &options), (Limit::Values, 52, 48));
+}
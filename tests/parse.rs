@@ -29,3 +29,44 @@ fn parse_string_multiline() {
     assert_eq!(parsed.get("str2").unwrap(), &shaun::Shaun::from("hello\n  there"));
     assert_eq!(parsed.get("str3").unwrap(), &shaun::Shaun::from("hello\n there"));
 }
+
+#[test]
+fn parse_reader_buf_read() {
+    let input = std::io::BufReader::new("{ hello : true }".as_bytes());
+    assert_eq!(shaun::parse_reader(input).unwrap(), shaun::parse_str("hello : true"))
+}
+
+#[test]
+fn parse_reader_invalid_utf8() {
+    let err = shaun::parse_reader(&b"hello : \"w\xc3\x28rld\""[..]).unwrap_err();
+    match err.kind() {
+        &shaun::ErrorKind::InvalidUtf8 => (),
+        k => panic!("unexpected error kind {:?}", k),
+    }
+}
+
+#[test]
+fn parse_documents() {
+    let input = "---\n# first\na: 1\n---\n{ b: true }\n---\n---\nc: \"x\"\n---\n";
+    let docs = shaun::parse_all_documents(input.as_bytes()).unwrap();
+    assert_eq!(docs, vec![
+        shaun::parse_str("a: 1"),
+        shaun::parse_str("b: true"),
+        shaun::parse_str(""),
+        shaun::parse_str("c: \"x\""),
+    ]);
+}
+
+#[test]
+fn parse_single_document_rejects_separator() {
+    assert!(shaun::parse_reader("a: 1\n---\nb: 2".as_bytes()).is_err());
+}
+
+#[test]
+fn parse_separator_on_its_own_line() {
+    let docs = shaun::parse_all_documents("a: 1\n--- \t\nb: 2".as_bytes()).unwrap();
+    assert_eq!(docs.len(), 2);
+    for input in &["a: 1 --- b: 2", "a: 1\n --- \nb: 2", "a: 1\n--- b: 2"] {
+        assert!(shaun::parse_all_documents(input.as_bytes()).is_err(), "{:?}", input);
+    }
+}
@@ -0,0 +1,105 @@
+extern crate shaun;
+
+use shaun::{ErrorKind, Event, Num, Reader};
+
+fn events(s:&str) -> Vec<Event> {
+    Reader::new(s.as_bytes()).map(|e| e.unwrap()).collect()
+}
+
+#[test]
+fn read_nested_events() {
+    let evs = events("{ a: [1 m, \"x\"] # note\n b: { c: null } }");
+    assert_eq!(evs, vec![
+        Event::StartObject,
+        Event::Key("a".to_string()),
+        Event::StartList,
+        Event::Number(Num::UInt(1), "m".to_string()),
+        Event::String("x".to_string()),
+        Event::EndList,
+        Event::Comment(" note".to_string()),
+        Event::Key("b".to_string()),
+        Event::StartObject,
+        Event::Key("c".to_string()),
+        Event::Null,
+        Event::EndObject,
+        Event::EndObject,
+    ])
+}
+
+#[test]
+fn read_unit_not_next_key() {
+    let evs = events("a: 10\nb: 20 s");
+    assert_eq!(evs[2], Event::Number(Num::UInt(10), String::new()));
+    assert_eq!(evs[3], Event::Key("b".to_string()));
+    assert_eq!(evs[4], Event::Number(Num::UInt(20), "s".to_string()));
+}
+
+#[test]
+fn read_empty_input() {
+    assert_eq!(events(""), vec![Event::StartObject, Event::EndObject])
+}
+
+#[test]
+fn read_unclosed_object() {
+    let mut reader = Reader::new("{ a: { b: 1 }\n".as_bytes());
+    let err = reader.find(|e| e.is_err()).unwrap().unwrap_err();
+    match err.kind() {
+        &ErrorKind::UnexpectedEof => (),
+        k => panic!("unexpected error kind {:?}", k),
+    }
+    assert_eq!(err.line(), 2);
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn read_number_literals() {
+    let evs = events("a: 0xFF b: 0b1010 c: 1_000_000 d: -inf e: nan f: 18446744073709551615
+                      g: -9223372036854775808 h: 0o17 i: 1.5e3 j: 100000000000000000000 k: 0B l: 0xF_F");
+    let values : Vec<Num> = evs.into_iter().filter_map(|e| match e {
+        Event::Number(n, _) => Some(n),
+        _ => None,
+    }).collect();
+
+    assert_eq!(values[0], Num::UInt(255));
+    assert_eq!(values[1], Num::UInt(10));
+    assert_eq!(values[2], Num::UInt(1_000_000));
+    assert_eq!(values[3], Num::Float(f64::NEG_INFINITY));
+    assert!(values[4].as_f64().is_nan());
+    assert_eq!(values[5].as_u64(), Some(u64::MAX));
+    assert_eq!(values[6].as_i64(), Some(i64::MIN));
+    assert_eq!(values[7], Num::UInt(15));
+    assert_eq!(values[8], Num::Float(1500.0));
+    assert_eq!(values[9].as_u64(), None);
+    assert_eq!(values[9].as_f64(), 1e20);
+    assert_eq!(values[10], Num::UInt(0));
+    assert_eq!(values[11], Num::UInt(255));
+}
+
+#[test]
+fn read_invalid_numbers() {
+    for input in &["a: 0x", "a: 1__0", "a: 1_", "a: 0x1FFFFFFFFFFFFFFFF", "a: 1.2.3", "a: 0b102", "a: -foo", "a: 1_e5", "a: 1_a"] {
+        let err = Reader::new(input.as_bytes()).find_map(|e| e.err()).unwrap();
+        match err.kind() {
+            ErrorKind::InvalidNumber(_) => assert_eq!(err.position().unwrap().column, 4, "{}", input),
+            k => panic!("unexpected error kind {:?} for {}", k, input),
+        }
+    }
+}
+
+#[test]
+fn read_special_floats_with_any_sign() {
+    let evs = events("a: inf b: +inf c: -inf d: nan e: -nan");
+    let values : Vec<f64> = evs.into_iter().filter_map(|e| match e {
+        Event::Number(n, _) => Some(n.as_f64()),
+        _ => None,
+    }).collect();
+    assert_eq!(&values[..3], &[f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY]);
+    assert!(values[3].is_nan() && values[4].is_nan());
+
+    // other spellings are words without a sign, and invalid with one
+    assert_eq!(events("a: infinity")[2], Event::String("infinity".to_string()));
+    for input in &["a: -infinity", "a: +infinity"] {
+        let err = Reader::new(input.as_bytes()).find_map(|e| e.err()).unwrap();
+        assert!(matches!(err.kind(), ErrorKind::InvalidNumber(_)), "{}", input);
+    }
+}
@@ -0,0 +1,43 @@
+extern crate shaun;
+
+use shaun::{records, RecordWriter, Shaun};
+
+#[test]
+fn records_resynchronize() {
+    let input = "{ id: 1 }\n\n  id: 2 ok: true\n{ id: ]\n{ id: 4 }";
+    let mut it = records(input.as_bytes());
+
+    assert_eq!(it.next().unwrap().unwrap().get("id").unwrap(), &Shaun::from(1.0));
+    assert_eq!((it.index(), it.line()), (0, 1));
+    assert_eq!(it.next().unwrap().unwrap().get("ok").unwrap(), &Shaun::from(true));
+    assert_eq!((it.index(), it.line()), (1, 3));
+
+    let err = it.next().unwrap().unwrap_err();
+    assert_eq!(err.position().unwrap().line, 4);
+    assert_eq!(it.index(), 2);
+
+    assert_eq!(it.next().unwrap().unwrap().get("id").unwrap(), &Shaun::from(4.0));
+    assert!(it.next().is_none());
+}
+
+#[test]
+fn records_round_trip() {
+    let values = vec![
+        shaun::parse_str("a: [1 m, 2 m] b: { c: \"multi\nline\" }"),
+        shaun::parse_str("{ }"),
+    ];
+
+    let mut w = RecordWriter::new(Vec::new());
+    for v in values.iter() { w.write(v).unwrap() }
+    let out = w.into_inner();
+
+    assert_eq!(out.iter().filter(|b| **b == b'\n').count(), 2);
+    let read : Vec<Shaun> = records(&out[..]).map(|r| r.unwrap()).collect();
+    assert_eq!(read, values);
+}
+
+#[test]
+fn records_reject_non_objects() {
+    let mut w = RecordWriter::new(Vec::new());
+    assert!(w.write(&Shaun::from(1.0)).is_err());
+}
@@ -0,0 +1,91 @@
+extern crate shaun;
+
+use shaun::{parse_recovering, ErrorKind, ParseOptions, Shaun};
+
+fn recover(input:&str) -> (Shaun, Vec<String>) {
+    let (sn, errors) = parse_recovering(input.as_bytes(), &ParseOptions::default());
+    let errors = errors.iter().map(|e| {
+        let p = e.position().unwrap();
+        format!("{}:{} {}", p.line, p.column, e.kind())
+    }).collect();
+    (sn, errors)
+}
+
+#[test]
+fn recover_in_objects() {
+    let (sn, errors) = recover("
+        a: 1
+        b: 0x
+        c: b: 2
+        d: { e: true f: } g: 3
+    ");
+
+    assert_eq!(errors, vec![
+        "3:12 invalid number `0x`",
+        "4:12 unexpected key `b`",
+        "5:25 unexpected `}`",
+    ]);
+    assert_eq!(sn.get("a").unwrap(), &Shaun::from(1.0));
+    assert!(sn.get("b").unwrap().is_number());
+    assert!(sn.get("c").is_err());
+    assert_eq!(sn.get("d").unwrap().get("e").unwrap(), &Shaun::Bool(true));
+    assert!(sn.get("d").unwrap().get("f").is_err());
+    assert_eq!(sn.get("g").unwrap(), &Shaun::from(3.0));
+}
+
+#[test]
+fn recover_in_lists() {
+    let (sn, errors) = recover("a: [1 0b2 3 } x: 4 ] b: 5");
+    assert_eq!(errors, vec!["1:7 invalid number `0b2`", "1:13 unexpected `}`"]);
+    assert_eq!(sn.get("a").unwrap(), &Shaun::from(vec![1.0, 3.0, 4.0]));
+    assert_eq!(sn.get("b").unwrap(), &Shaun::from(5.0));
+}
+
+#[test]
+fn recover_at_the_end() {
+    let (sn, errors) = recover("a: { b: [1 2");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(sn.get("a").unwrap().get("b").unwrap(), &Shaun::from(vec![1.0, 2.0]));
+
+    let (sn, errors) = recover("{ a: 1 } ] b");
+    assert_eq!(errors, vec!["1:10 unexpected `]`"]);
+    assert_eq!(sn.get("a").unwrap(), &Shaun::from(1.0));
+
+    let (sn, errors) = recover("a: *nope b: $(1 +) c: 1");
+    assert_eq!(errors.len(), 2);
+    assert!(sn.get("a").unwrap().is_null());
+    assert_eq!(sn.get("c").unwrap(), &Shaun::from(1.0));
+}
+
+#[test]
+fn stop_at_limits() {
+    let options = ParseOptions { max_list_length: Some(2), ..ParseOptions::default() };
+    let (sn, errors) = parse_recovering("a: 1 b: [1 2 3] c: 0x".as_bytes(), &options);
+    assert_eq!(errors.len(), 1);
+    match errors[0].kind() {
+        ErrorKind::LimitExceeded(_, 2) => (),
+        k => panic!("unexpected error kind {:?}", k),
+    }
+    assert_eq!(sn.get("a").unwrap(), &Shaun::from(1.0));
+    assert_eq!(sn.get("b").unwrap(), &Shaun::from(vec![1.0, 2.0]));
+}
+
+#[test]
+fn recover_errors_while_skipping() {
+    let strict = ParseOptions { strict: true, ..ParseOptions::default() };
+    let (sn, errors) = parse_recovering("a: \"\\q\" b: 4".as_bytes(), &strict);
+    assert_eq!(errors.len(), 1);
+    match errors[0].kind() {
+        ErrorKind::InvalidEscape(e) => assert_eq!(e, "\\q"),
+        k => panic!("unexpected error kind {:?}", k),
+    }
+    assert_eq!(sn.get("b").unwrap(), &Shaun::from(4.0));
+
+    // the errors found while skipping to the next key are reported too
+    let (sn, errors) = recover("a: 0x 0b2 0o9 b: 4");
+    assert_eq!(errors, vec!["1:4 invalid number `0x`", "1:7 invalid number `0b2`", "1:11 invalid number `0o9`"]);
+    assert_eq!(sn.get("b").unwrap(), &Shaun::from(4.0));
+
+    let (_, errors) = recover("a: 0x \"b");
+    assert_eq!(errors, vec!["1:4 invalid number `0x`", "1:7 unexpected end of input"]);
+}
@@ -0,0 +1,54 @@
+extern crate shaun;
+
+use shaun::{parse_unresolved, ErrorKind, Shaun, Writer};
+
+#[test]
+fn resolve_aliases() {
+    let sn = shaun::parse_str("
+        defaults: &defaults { timeout: 30 s retries: 3 }
+        services: [
+            { name: \"a\" settings: *defaults }
+            { name: \"b\" settings: *ports }
+        ]
+        ports: &ports [ 80 *http ]
+        http: &http 8080
+    ");
+    let defaults = sn.get("defaults").unwrap();
+    let services = sn.get("services").unwrap();
+    assert_eq!(services.at(0usize).unwrap().get("settings").unwrap(), defaults);
+    assert_eq!(services.at(1usize).unwrap().get("settings").unwrap(),
+               &Shaun::from(vec![80.0, 8080.0]));
+}
+
+#[test]
+fn dangling_and_cyclic_references() {
+    let err = shaun::parse_reader("a: *nope".as_bytes()).unwrap_err();
+    match err.kind() {
+        ErrorKind::DanglingReference(name) => assert_eq!(name, "nope"),
+        k => panic!("unexpected error kind {:?}", k),
+    }
+
+    let err = shaun::parse_reader("a: &x { b: [ 1 *x ] }".as_bytes()).unwrap_err();
+    match err.kind() {
+        ErrorKind::CyclicReference(name) => assert_eq!(name, "x"),
+        k => panic!("unexpected error kind {:?}", k),
+    }
+
+    assert!(shaun::parse_reader("a: &x 1 b: &x 2".as_bytes()).is_err());
+}
+
+#[test]
+fn round_trip_unresolved() {
+    let doc = parse_unresolved("base: &b { port: 80 } web: *b list: [ &one 1 *one ]".as_bytes()).unwrap();
+    assert!(!doc.is_resolved());
+
+    let mut w = Writer::new(Vec::new());
+    doc.write(&mut w).unwrap();
+    let out = String::from_utf8(w.finish().unwrap()).unwrap();
+    assert!(out.contains("&b {"));
+    assert!(out.contains("web: *b"));
+
+    let again = parse_unresolved(out.as_bytes()).unwrap();
+    assert_eq!(again.value(), doc.value());
+    assert_eq!(again.resolve().unwrap(), doc.resolve().unwrap());
+}
@@ -0,0 +1,124 @@
+extern crate shaun;
+
+use shaun::{ErrorKind, PrettyPrinter, Reader, Shaun, Writer};
+
+#[test]
+fn read_escapes() {
+    let sn = shaun::parse_str(r#"
+        a: "tab\there\0\b\f"
+        b: "\u00e9t\u00E9 \u{1F600} \uD83D\uDE00"
+        c: "\"quoted\" \\ \/ \'"
+        d: "ends with a line\n"
+    "#);
+
+    assert_eq!(sn.get("a").unwrap(), &Shaun::from("tab\there\0\u{8}\u{c}"));
+    assert_eq!(sn.get("b").unwrap(), &Shaun::from("été 😀 😀"));
+    assert_eq!(sn.get("c").unwrap(), &Shaun::from("\"quoted\" \\ / '"));
+    assert_eq!(sn.get("d").unwrap(), &Shaun::from("ends with a line\n"));
+}
+
+#[test]
+fn read_raw_strings() {
+    let sn = shaun::parse_str(r###"
+        path: r"C:\new\dir"
+        quote: r##"say "hi"# then"##
+        r: 1
+        "key": r"raw"
+    "###);
+
+    assert_eq!(sn.get("path").unwrap(), &Shaun::from(r"C:\new\dir"));
+    assert_eq!(sn.get("quote").unwrap(), &Shaun::from(r##"say "hi"# then"##));
+    assert_eq!(sn.get("r").unwrap(), &Shaun::from(1.0));
+    assert_eq!(sn.get("key").unwrap(), &Shaun::from("raw"));
+}
+
+#[test]
+fn invalid_escapes() {
+    let sn = shaun::parse_str(r#"a: "\q \uZZ \uD800x""#);
+    assert_eq!(sn.get("a").unwrap(), &Shaun::from("q uZZ uD800x"));
+
+    for input in &[r#"a: "\q""#, r#"a: "\uZZ""#, r#"a: "\u{110000}""#, r#"a: "\uD800x""#, r#"a: "\uDE00""#] {
+        let err = Reader::new(input.as_bytes()).strict().find_map(|e| e.err()).unwrap();
+        match err.kind() {
+            ErrorKind::InvalidEscape(_) => (),
+            k => panic!("unexpected error kind {:?} for {}", k, input),
+        }
+        assert_eq!(err.position().unwrap().column, 5);
+    }
+}
+
+#[test]
+fn strings_round_trip() {
+    let strings = vec!["\nleading and trailing\n", "quotes \" and \\", "control \u{1b}[0m \0 \u{8}\u{c}", "tab\t\r\nend", "ünicode 😀"];
+    let sn = Shaun::from(vec![strings.clone()]);
+
+    let mut w = Writer::new(Vec::new());
+    w.begin_object().unwrap();
+    w.key("list").unwrap();
+    w.value(&sn).unwrap();
+    w.key("with \"quotes\"").unwrap();
+    w.value(&Shaun::from("x")).unwrap();
+    w.end_object().unwrap();
+    let written = String::from_utf8(w.finish().unwrap()).unwrap();
+    let read = shaun::parse_str(&written);
+    assert_eq!(read.get("list").unwrap(), &sn);
+    assert_eq!(read.get("with \"quotes\"").unwrap(), &Shaun::from("x"));
+
+    let mut out = Vec::new();
+    Shaun::from(strings[2]).visit_with(&mut PrettyPrinter::to(&mut out));
+    assert_eq!(String::from_utf8(out).unwrap(), "\"control \\u001b[0m \\0 \\b\\f\"");
+}
+
+#[test]
+fn multiline_indentation() {
+    let sn = shaun::parse_str("
+        text: \"
+            first
+              second
+
+            \\tthird
+            \"
+        aligned: \"one
+                  two
+                    three\"
+        windows: \"\r\n  x\r\n  y\r\n  \"
+    ");
+
+    assert_eq!(sn.get("text").unwrap(), &Shaun::from("first\n  second\n\n\tthird"));
+    assert_eq!(sn.get("aligned").unwrap(), &Shaun::from("one\ntwo\n  three"));
+    assert_eq!(sn.get("windows").unwrap(), &Shaun::from("x\ny"));
+}
+
+#[test]
+fn folded_strings() {
+    let sn = shaun::parse_str("
+        about: >\"
+            A paragraph written
+            on several lines.
+
+            Another one.
+            \"
+        short: >\"single line\"
+    ");
+
+    assert_eq!(sn.get("about").unwrap(), &Shaun::from("A paragraph written on several lines.\nAnother one."));
+    assert_eq!(sn.get("short").unwrap(), &Shaun::from("single line"));
+}
+
+#[test]
+fn write_multiline_strings() {
+    let text = "A long text, over several lines:\n  - indented \"item\"\n\n   \nand a line break at the end\n";
+    let mut sn = shaun::parse_str("short: \"a\\nb\"");
+    sn.to_mut_map().unwrap().insert("text".to_string(), Shaun::from(text));
+
+    let mut w = Writer::new(Vec::new());
+    w.value(&sn).unwrap();
+    let written = String::from_utf8(w.finish().unwrap()).unwrap();
+    assert!(written.contains("text: \"\n    A long text, over several lines:\n      - indented \\\"item\\\"\n\n"));
+    assert!(written.contains("short: \"a\\nb\""));
+    assert_eq!(shaun::parse_str(&written), sn);
+
+    let mut out = Vec::new();
+    sn.visit_with(&mut PrettyPrinter::to(&mut out));
+    assert_eq!(shaun::parse_str(&String::from_utf8(out).unwrap()), sn);
+}
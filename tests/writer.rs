@@ -0,0 +1,84 @@
+extern crate shaun;
+
+use shaun::{Shaun, Writer};
+
+fn written<F>(f:F) -> String where F : FnOnce(&mut Writer<Vec<u8>>) -> std::io::Result<()> {
+    let mut w = Writer::new(Vec::new());
+    f(&mut w).unwrap();
+    String::from_utf8(w.finish().unwrap()).unwrap()
+}
+
+#[test]
+fn write_nested() {
+    let out = written(|w| {
+        w.comment("generated")?;
+        w.begin_object()?;
+        w.key("name")?; w.string("a \"quoted\"\nline")?;
+        w.key("sizes")?; w.begin_list()?;
+        w.number_with_unit(4.0, "KiB")?; w.number(0.5)?;
+        w.end_list()?;
+        w.key("empty")?; w.begin_object()?; w.end_object()?;
+        w.end_object()
+    });
+    assert_eq!(out, "# generated\n{\n  name: \"a \\\"quoted\\\"\\nline\"\n  sizes: [\n    4 KiB\n    0.5\n  ]\n  empty: {}\n}\n");
+}
+
+#[test]
+fn write_round_trip() {
+    let sn = shaun::parse_str("a: [1 m, \"x\\ty\", true, null] b: { c: \"\\\\\" }");
+    let out = written(|w| w.value(&sn));
+    assert_eq!(shaun::parse_str(&out), sn);
+}
+
+#[test]
+fn write_misuse() {
+    let mut w = Writer::new(Vec::new());
+    w.begin_object().unwrap();
+    assert!(w.number(1.0).is_err());
+    assert!(w.end_list().is_err());
+    w.key("a").unwrap();
+    assert!(w.comment("not here").is_err());
+    assert!(w.finish().is_err());
+}
+
+#[test]
+fn write_propagates_io_errors() {
+    struct Failing;
+    impl std::io::Write for Failing {
+        fn write(&mut self, _:&[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("disk full"))
+        }
+        fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+    }
+    let mut w = Writer::new(Failing);
+    assert!(w.value(&Shaun::from(true)).is_err());
+}
+
+#[test]
+fn write_quoted_keys() {
+    let sn = shaun::parse_str("{ \"content-type\": 1 app.name: \"x\" \"my key\": 2 \"\": 3 x-forwarded-for: 4 }");
+    assert_eq!(sn.get("content-type").unwrap(), &Shaun::from(1.0));
+    assert_eq!(sn.get("app.name").unwrap(), &Shaun::from("x"));
+    assert_eq!(sn.get("my key").unwrap(), &Shaun::from(2.0));
+    assert_eq!(sn.get("").unwrap(), &Shaun::from(3.0));
+
+    let out = written(|w| w.value(&sn));
+    assert!(out.contains("\"my key\": 2"));
+    assert!(out.contains("app.name: \"x\""));
+    assert_eq!(shaun::parse_str(&out), sn);
+
+    let mut pretty = Vec::new();
+    sn.visit_with(&mut shaun::PrettyPrinter::to(&mut pretty));
+    assert_eq!(shaun::parse_str(&String::from_utf8(pretty).unwrap()), sn);
+}
+
+#[test]
+fn dashes_and_dots_only_in_keys() {
+    let sn = shaun::parse_str("{ speed: [10 km] log.level : 2 }");
+    assert_eq!(sn.get("speed").unwrap(), &Shaun::from(vec![Shaun::Number(10.0.into(), "km".to_string())]));
+    assert_eq!(sn.get("log.level").unwrap(), &Shaun::from(2.0));
+
+    for input in &["a: 10 km-h", "a: x.y", "a: [b-c]"] {
+        assert!(shaun::parse_reader(input.as_bytes()).is_err(), "{}", input);
+    }
+}